@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::fs::Entry;
+
+#[derive(Debug)]
+pub(super) enum Warning {
+    Malformed {
+        line: usize,
+        text: String,
+    },
+    UnknownCommand {
+        line: usize,
+        command: String,
+    },
+    FileBecameDirectory {
+        line: usize,
+        path: String,
+    },
+    DirectoryBecameFile {
+        line: usize,
+        path: String,
+    },
+    SizeChanged {
+        line: usize,
+        path: String,
+        old: usize,
+        new: usize,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed { line, text } => write!(f, "line {line}: cannot parse {text:?}"),
+            Self::UnknownCommand { line, command } => {
+                write!(f, "line {line}: unknown command {command:?}")
+            }
+            Self::FileBecameDirectory { line, path } => {
+                write!(
+                    f,
+                    "line {line}: {path} was a file, now treated as a directory"
+                )
+            }
+            Self::DirectoryBecameFile { line, path } => {
+                write!(
+                    f,
+                    "line {line}: {path} was a directory, now treated as a file"
+                )
+            }
+            Self::SizeChanged {
+                line,
+                path,
+                old,
+                new,
+            } => write!(f, "line {line}: {path} changed size from {old} to {new}"),
+        }
+    }
+}
+
+// Applies a `cd` argument to the current path. Handles absolute paths,
+// multiple segments, `.` and `..` (which stops at the root).
+fn resolve(cwd: &[String], target: &str) -> Vec<String> {
+    let mut path = if target.starts_with('/') {
+        Vec::new()
+    } else {
+        cwd.to_vec()
+    };
+
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => (),
+            ".." => {
+                path.pop();
+            }
+            _ => path.push(segment.to_owned()),
+        }
+    }
+
+    path
+}
+
+fn display_path(path: &[String]) -> String {
+    format!("/{}", path.join("/"))
+}
+
+// Walks to the directory at `path`, creating any directory that has not been
+// listed yet. A file standing in the way is replaced by a directory.
+fn make_dirs<'a>(
+    root: &'a mut Entry,
+    path: &[String],
+    line: usize,
+    warnings: &mut Vec<Warning>,
+) -> &'a mut BTreeMap<String, Entry> {
+    let mut entries = match root {
+        Entry::Directory(entries) => entries,
+        Entry::File(_) => panic!("not a dir"),
+    };
+
+    for (i, name) in path.iter().enumerate() {
+        let entry = entries
+            .entry(name.clone())
+            .or_insert_with(|| Entry::Directory(BTreeMap::new()));
+
+        if let Entry::File(_) = entry {
+            warnings.push(Warning::FileBecameDirectory {
+                line,
+                path: display_path(&path[..=i]),
+            });
+            *entry = Entry::Directory(BTreeMap::new());
+        }
+
+        entries = match entry {
+            Entry::Directory(children) => children,
+            Entry::File(_) => unreachable!(),
+        };
+    }
+
+    entries
+}
+
+pub(super) fn parse<I>(lines: I) -> (Entry, Vec<Warning>)
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut root = Entry::Directory(BTreeMap::new());
+    let mut cwd = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (index, line) in lines.into_iter().enumerate() {
+        let line_no = index + 1;
+        let line = line.as_ref().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(command) = line.strip_prefix('$') {
+            let command = command.trim();
+
+            let (verb, arg) = command
+                .split_once(char::is_whitespace)
+                .unwrap_or((command, ""));
+
+            match verb {
+                "cd" => {
+                    cwd = resolve(&cwd, arg.trim());
+                    make_dirs(&mut root, &cwd, line_no, &mut warnings);
+                }
+                "ls" => (),
+                _ => warnings.push(Warning::UnknownCommand {
+                    line: line_no,
+                    command: command.to_owned(),
+                }),
+            }
+
+            continue;
+        }
+
+        let Some((desc, name)) = line.split_once(char::is_whitespace) else {
+            warnings.push(Warning::Malformed {
+                line: line_no,
+                text: line.to_owned(),
+            });
+            continue;
+        };
+        let name = name.trim();
+
+        let listed = if desc == "dir" {
+            Entry::Directory(BTreeMap::new())
+        } else if let Ok(size) = desc.parse() {
+            Entry::File(size)
+        } else {
+            warnings.push(Warning::Malformed {
+                line: line_no,
+                text: line.to_owned(),
+            });
+            continue;
+        };
+
+        let path = || {
+            let mut path = cwd.clone();
+            path.push(name.to_owned());
+            display_path(&path)
+        };
+
+        let dir = make_dirs(&mut root, &cwd, line_no, &mut warnings);
+        match (dir.get_mut(name), listed) {
+            (None, listed) => {
+                dir.insert(name.to_owned(), listed);
+            }
+            (Some(Entry::Directory(_)), Entry::Directory(_)) => (),
+            (Some(Entry::File(old)), Entry::File(new)) => {
+                if *old != new {
+                    warnings.push(Warning::SizeChanged {
+                        line: line_no,
+                        path: path(),
+                        old: *old,
+                        new,
+                    });
+                    *old = new;
+                }
+            }
+            (Some(existing @ Entry::File(_)), listed @ Entry::Directory(_)) => {
+                warnings.push(Warning::FileBecameDirectory {
+                    line: line_no,
+                    path: path(),
+                });
+                *existing = listed;
+            }
+            (Some(existing @ Entry::Directory(_)), listed @ Entry::File(_)) => {
+                warnings.push(Warning::DirectoryBecameFile {
+                    line: line_no,
+                    path: path(),
+                });
+                *existing = listed;
+            }
+        }
+    }
+
+    (root, warnings)
+}
@@ -0,0 +1,251 @@
+use std::collections::btree_map::{BTreeMap, Iter};
+use std::fmt;
+
+#[derive(Debug)]
+pub(super) enum Entry {
+    Directory(BTreeMap<String, Entry>),
+    File(usize),
+}
+
+impl Entry {
+    pub(super) fn is_dir(&self) -> bool {
+        matches!(self, Self::Directory(_))
+    }
+
+    pub(super) fn iter(&self) -> EntryIterator<'_> {
+        if let Self::Directory(_) = self {
+            EntryIterator::new(self)
+        } else {
+            panic!("not a dir");
+        }
+    }
+
+    pub(super) fn du(&self) -> DiskUsage {
+        let mut sizes = BTreeMap::new();
+        self.du_into("/", &mut sizes);
+        DiskUsage { sizes }
+    }
+
+    fn du_into(&self, path: &str, sizes: &mut BTreeMap<String, usize>) -> usize {
+        match self {
+            Self::File(size) => *size,
+            Self::Directory(entries) => {
+                let total = entries
+                    .iter()
+                    .map(|(name, entry)| entry.du_into(&join(path, name), sizes))
+                    .sum();
+                sizes.insert(path.to_owned(), total);
+                total
+            }
+        }
+    }
+
+    pub(super) fn find(&self) -> Find<'_> {
+        Find::new(self)
+    }
+
+    pub(super) fn tree(&self) -> Tree<'_> {
+        Tree {
+            root: self,
+            usage: self.du(),
+        }
+    }
+}
+
+pub(super) fn join(path: &str, name: &str) -> String {
+    if path == "/" {
+        format!("/{name}")
+    } else {
+        format!("{path}/{name}")
+    }
+}
+
+pub(super) struct EntryIterator<'a> {
+    root: &'a Entry,
+    stack: Vec<(String, Iter<'a, String, Entry>)>,
+}
+
+impl<'a> EntryIterator<'a> {
+    fn new(root: &'a Entry) -> Self {
+        Self {
+            root,
+            stack: Vec::new(),
+        }
+    }
+}
+
+impl<'a> Iterator for EntryIterator<'a> {
+    type Item = (String, &'a Entry);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.stack.is_empty() {
+            if let Entry::Directory(entries) = self.root {
+                self.stack.push((String::new(), entries.iter()));
+                return Some(("/".into(), self.root));
+            } else {
+                panic!("not a dir");
+            }
+        }
+
+        loop {
+            let (path, iter) = self.stack.last_mut()?;
+
+            if let Some((name, entry)) = iter.next() {
+                let mut path = path.clone();
+                path.push('/');
+                path.push_str(name);
+
+                if let Entry::Directory(entries) = entry {
+                    self.stack.push((path.clone(), entries.iter()));
+                }
+
+                return Some((path, entry));
+            } else {
+                self.stack.pop();
+            }
+        }
+    }
+}
+
+// Directory sizes computed in a single post-order walk, keyed by path.
+pub(super) struct DiskUsage {
+    sizes: BTreeMap<String, usize>,
+}
+
+impl DiskUsage {
+    pub(super) fn get(&self, path: &str) -> Option<usize> {
+        self.sizes.get(path).copied()
+    }
+
+    pub(super) fn total(&self) -> usize {
+        self.get("/").unwrap_or(0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Kind {
+    Directory,
+    File,
+}
+
+pub(super) struct Find<'a> {
+    root: &'a Entry,
+    name: Option<String>,
+    kind: Option<Kind>,
+    size: Option<Box<dyn Fn(usize) -> bool + 'a>>,
+}
+
+impl<'a> Find<'a> {
+    fn new(root: &'a Entry) -> Self {
+        Self {
+            root,
+            name: None,
+            kind: None,
+            size: None,
+        }
+    }
+
+    pub(super) fn name(mut self, pattern: &str) -> Self {
+        self.name = Some(pattern.to_owned());
+        self
+    }
+
+    pub(super) fn kind(mut self, kind: Kind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    pub(super) fn size<P>(mut self, predicate: P) -> Self
+    where
+        P: Fn(usize) -> bool + 'a,
+    {
+        self.size = Some(Box::new(predicate));
+        self
+    }
+
+    pub(super) fn run(&self) -> Vec<(String, usize)> {
+        let usage = self.root.du();
+
+        self.root
+            .iter()
+            .filter_map(|(path, entry)| {
+                let kind = if entry.is_dir() {
+                    Kind::Directory
+                } else {
+                    Kind::File
+                };
+                if self.kind.is_some_and(|k| k != kind) {
+                    return None;
+                }
+
+                if let Some(pattern) = &self.name {
+                    let name = path.rsplit('/').next().unwrap_or_default();
+                    if !glob_match(pattern.as_bytes(), name.as_bytes()) {
+                        return None;
+                    }
+                }
+
+                let size = match entry {
+                    Entry::File(size) => *size,
+                    Entry::Directory(_) => usage.get(&path).unwrap(),
+                };
+                if self.size.as_ref().is_some_and(|p| !p(size)) {
+                    return None;
+                }
+
+                Some((path, size))
+            })
+            .collect()
+    }
+}
+
+// Shell-style glob supporting `*` and `?`.
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            glob_match(&pattern[1..], name) || (!name.is_empty() && glob_match(pattern, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => glob_match(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => glob_match(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+pub(super) struct Tree<'a> {
+    root: &'a Entry,
+    usage: DiskUsage,
+}
+
+impl Tree<'_> {
+    fn write_entry(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        path: &str,
+        name: &str,
+        entry: &Entry,
+        depth: usize,
+    ) -> fmt::Result {
+        let indent = depth * 2;
+
+        match entry {
+            Entry::File(size) => writeln!(f, "{:indent$}- {name} (file, size={size})", ""),
+            Entry::Directory(entries) => {
+                let size = self.usage.get(path).unwrap();
+                writeln!(f, "{:indent$}- {name} (dir, size={size})", "")?;
+
+                for (child, entry) in entries {
+                    self.write_entry(f, &join(path, child), child, entry, depth + 1)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Tree<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_entry(f, "/", "/", self.root, 0)
+    }
+}
@@ -1,147 +1,73 @@
-use std::collections::btree_map::{BTreeMap, Iter};
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 
-#[derive(Debug)]
-enum Entry {
-    Directory(BTreeMap<String, Entry>),
-    File(usize),
-}
-
-impl Entry {
-    fn lookup_dir_mut(&mut self, path: &[String]) -> &mut BTreeMap<String, Entry> {
-        if let Self::Directory(entries) = self {
-            if path.is_empty() {
-                entries
-            } else {
-                entries
-                    .get_mut(&path[0])
-                    .unwrap()
-                    .lookup_dir_mut(&path[1..])
-            }
-        } else {
-            panic!("not a dir");
-        }
-    }
-
-    fn size(&self) -> usize {
-        match self {
-            Self::File(size) => *size,
-            Self::Directory(entries) => entries.values().map(|e| e.size()).sum(),
-        }
-    }
-
-    fn iter<'a>(&'a self) -> EntryIterator<'a> {
-        if let Self::Directory(_) = self {
-            EntryIterator::new(self)
-        } else {
-            panic!("not a dir");
-        }
-    }
-}
-
-struct EntryIterator<'a> {
-    root: &'a Entry,
-    stack: Vec<(String, Iter<'a, String, Entry>)>,
-}
-
-impl<'a> EntryIterator<'a> {
-    fn new(root: &'a Entry) -> Self {
-        Self {
-            root,
-            stack: Vec::new(),
-        }
-    }
-}
-
-impl<'a> Iterator for EntryIterator<'a> {
-    type Item = (String, &'a Entry);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.stack.is_empty() {
-            if let Entry::Directory(entries) = self.root {
-                self.stack.push((String::new(), entries.iter()));
-                return Some(("/".into(), self.root));
-            } else {
-                panic!("not a dir");
-            }
-        }
-
-        loop {
-            let (path, iter) = self.stack.last_mut()?;
-
-            if let Some((name, entry)) = iter.next() {
-                let mut path = path.clone();
-                path.push_str("/");
-                path.push_str(name);
-
-                if let Entry::Directory(entries) = entry {
-                    self.stack.push((path.clone(), entries.iter()));
+mod fs;
+mod plan;
+mod transcript;
+
+use fs::{Find, Kind};
+use plan::{Planner, Strategy};
+
+fn parse_find<'a>(mut find: Find<'a>, args: &'a [String]) -> Find<'a> {
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let value = args.next().expect("missing argument value");
+
+        match arg.as_str() {
+            "-name" => find = find.name(value),
+            "-type" => match value.as_str() {
+                "d" => find = find.kind(Kind::Directory),
+                "f" => find = find.kind(Kind::File),
+                _ => panic!("unknown type {value}"),
+            },
+            "-size" => {
+                if let Some(limit) = value.strip_prefix('+') {
+                    let limit: usize = limit.parse().unwrap();
+                    find = find.size(move |size| size > limit);
+                } else if let Some(limit) = value.strip_prefix('-') {
+                    let limit: usize = limit.parse().unwrap();
+                    find = find.size(move |size| size < limit);
+                } else {
+                    let limit: usize = value.parse().unwrap();
+                    find = find.size(move |size| size == limit);
                 }
-
-                return Some((path, entry));
-            } else {
-                self.stack.pop();
             }
+            _ => panic!("unknown predicate {arg}"),
         }
     }
+
+    find
 }
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
     let input = BufReader::new(File::open(&args[1]).unwrap());
 
-    let mut root = Entry::Directory(BTreeMap::new());
-    let mut path_stack = Vec::new();
-
-    for line in input.lines() {
-        let line = line.unwrap();
+    let (root, warnings) = transcript::parse(input.lines().map(|l| l.unwrap()));
+    for warning in &warnings {
+        eprintln!("warning: {warning}");
+    }
 
-        if let Some(path) = line.strip_prefix("$ cd ") {
-            match path {
-                "/" => {
-                    path_stack = Vec::new();
-                }
-                ".." => {
-                    path_stack.pop();
-                }
-                _ => {
-                    path_stack.push(path.to_owned());
-                }
+    match args.get(2).map(String::as_str) {
+        Some("tree") => print!("{}", root.tree()),
+        Some("find") => {
+            for (path, size) in parse_find(root.find(), &args[3..]).run() {
+                println!("{path:60} - {size:10}");
             }
-        } else if !line.starts_with("$ ") {
-            let (desc, name) = line.split_once(' ').unwrap();
-            let entry = if desc == "dir" {
-                Entry::Directory(BTreeMap::new())
-            } else {
-                Entry::File(desc.parse().unwrap())
-            };
-
-            let cwd = root.lookup_dir_mut(&path_stack);
-            cwd.insert(name.to_owned(), entry);
         }
-    }
-
-    let avail = 70000000 - root.size();
-    let needed = 30000000 - avail;
+        Some("plan") => {
+            let planner = Planner::new(args[3].parse().unwrap(), args[4].parse().unwrap());
+            print!("{}", planner.plan(&root, Strategy::SmallestSingle));
+            print!("{}", planner.plan(&root, Strategy::MinimalSet));
+        }
+        _ => {
+            let plan = Planner::default().plan(&root, Strategy::SmallestSingle);
 
-    let mut sizes: Vec<(String, usize)> = root
-        .iter()
-        .filter_map(|(path, entry)| {
-            if matches!(entry, Entry::Directory(_)) {
-                Some((path, entry.size()))
-            } else {
-                None
+            for (path, size) in plan.victims().unwrap_or_default() {
+                println!("selected victim is {path} with size {size}");
             }
-        })
-        .collect();
-    sizes.sort_unstable_by_key(|(_, size)| *size);
-
-    for (path, size) in sizes {
-        if size >= needed {
-            println!("selected victim is {path} with size {size}");
-            break;
         }
     }
 }
@@ -0,0 +1,432 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::fs::{join, DiskUsage, Entry};
+
+type Victims = Vec<(String, usize)>;
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Strategy {
+    SmallestSingle,
+    MinimalSet,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Planner {
+    disk_size: usize,
+    needed: usize,
+}
+
+impl Default for Planner {
+    fn default() -> Self {
+        Self::new(70000000, 30000000)
+    }
+}
+
+impl Planner {
+    pub(super) fn new(disk_size: usize, needed: usize) -> Self {
+        Self { disk_size, needed }
+    }
+
+    pub(super) fn plan(&self, root: &Entry, strategy: Strategy) -> Plan {
+        let usage = root.du();
+        let used = usage.total();
+        let available = self.disk_size.saturating_sub(used);
+        let target = self.needed.saturating_sub(available);
+
+        let (victims, runner_up) = if target == 0 {
+            (Some(Vec::new()), None)
+        } else {
+            match strategy {
+                Strategy::SmallestSingle => smallest_single(root, &usage, target),
+                Strategy::MinimalSet => {
+                    let single = smallest_single(root, &usage, target);
+                    let limit = single
+                        .0
+                        .as_ref()
+                        .map_or(usize::MAX, |v| v.iter().map(|(_, s)| s).sum());
+                    (minimal_set(root, &usage, target, limit).or(single.0), None)
+                }
+            }
+        };
+
+        Plan {
+            strategy,
+            disk_size: self.disk_size,
+            needed: self.needed,
+            used,
+            target,
+            victims,
+            runner_up,
+        }
+    }
+}
+
+// The original part 2 answer: the smallest directory that frees enough on its
+// own. Also returns the largest directory that falls short, for the report.
+fn smallest_single(
+    root: &Entry,
+    usage: &DiskUsage,
+    target: usize,
+) -> (Option<Victims>, Option<(String, usize)>) {
+    let mut best: Option<(String, usize)> = None;
+    let mut runner_up: Option<(String, usize)> = None;
+
+    for (path, entry) in root.iter() {
+        if !entry.is_dir() {
+            continue;
+        }
+
+        let size = usage.get(&path).unwrap();
+        if size >= target {
+            if best.as_ref().is_none_or(|(_, s)| size < *s) {
+                best = Some((path, size));
+            }
+        } else if runner_up.as_ref().is_none_or(|(_, s)| size > *s) {
+            runner_up = Some((path, size));
+        }
+    }
+
+    (best.map(|victim| vec![victim]), runner_up)
+}
+
+// Directories in post-order, each with its size and the index at which its
+// subtree begins. A directory's descendants are exactly the entries between
+// that start index and itself.
+fn postorder(entry: &Entry, path: &str, usage: &DiskUsage, out: &mut Vec<(String, usize, usize)>) {
+    let Entry::Directory(entries) = entry else {
+        return;
+    };
+
+    let start = out.len();
+    for (name, child) in entries {
+        postorder(child, &join(path, name), usage, out);
+    }
+
+    out.push((path.to_owned(), usage.get(path).unwrap(), start));
+}
+
+// The lowest set bit at or after `from`.
+fn first_set(bits: &[u64], from: usize) -> Option<usize> {
+    let (word, bit) = (from / 64, from % 64);
+
+    let first = bits.get(word)? & (!0 << bit);
+    if first != 0 {
+        return Some(word * 64 + first.trailing_zeros() as usize);
+    }
+
+    bits[word + 1..]
+        .iter()
+        .enumerate()
+        .find(|(_, w)| **w != 0)
+        .map(|(i, w)| (word + 1 + i) * 64 + w.trailing_zeros() as usize)
+}
+
+// Subset-sum over antichains of the directory tree. Walking in post-order, the
+// totals reachable once directory `k` is considered are those reachable before
+// it, plus `size_k` added to those reachable before its subtree started.
+// Totals only ever become reachable, so remembering the directory that first
+// reached each total is enough to walk the choice back afterwards.
+//
+// Only totals below `target` are tracked. Everything in the best set but its
+// last directory must add up to less than `target`, or that alone would be a
+// better set, so the best total at or above it is found as each directory is
+// added and kept to one side.
+fn minimal_set(root: &Entry, usage: &DiskUsage, target: usize, limit: usize) -> Option<Victims> {
+    if limit == usize::MAX {
+        return None;
+    } else if target == 0 {
+        return Some(Vec::new());
+    }
+
+    let mut dirs = Vec::new();
+    postorder(root, "/", usage, &mut dirs);
+
+    let mut last_use = HashMap::new();
+    for (index, (_, _, start)) in dirs.iter().enumerate() {
+        last_use.insert(*start, index);
+    }
+
+    let words = target.div_ceil(64);
+    // The bits of the final word that stand for totals below `target`.
+    let last_word = !0u64 >> (words * 64 - target);
+    let mut reachable = vec![0u64; words];
+    let mut first_reached = vec![u32::MAX; target];
+    let mut snapshots: HashMap<usize, Vec<u64>> = HashMap::new();
+
+    // The best total so far, the directory that completed it, and the total
+    // that directory was added to.
+    let mut best: Option<(usize, usize, usize)> = None;
+
+    reachable[0] = 1;
+
+    for (index, (_, size, start)) in dirs.iter().enumerate() {
+        if last_use.contains_key(&index) {
+            snapshots.insert(index, reachable.clone());
+        }
+
+        let before = &snapshots[start];
+
+        if let Some(base) = first_set(before, target.saturating_sub(*size)) {
+            let total = base + size;
+            if total <= limit && best.is_none_or(|(t, _, _)| total < t) {
+                best = Some((total, index, base));
+            }
+        }
+
+        if *size < target {
+            let (word_shift, bit_shift) = (size / 64, size % 64);
+
+            for word in word_shift..words {
+                let mut shifted = before[word - word_shift] << bit_shift;
+                if bit_shift > 0 && word > word_shift {
+                    shifted |= before[word - word_shift - 1] >> (64 - bit_shift);
+                }
+                if word == words - 1 {
+                    shifted &= last_word;
+                }
+
+                let mut fresh = shifted & !reachable[word];
+                reachable[word] |= fresh;
+
+                while fresh != 0 {
+                    let total = word * 64 + fresh.trailing_zeros() as usize;
+                    first_reached[total] = index as u32;
+                    fresh &= fresh - 1;
+                }
+            }
+        }
+
+        if last_use[start] == index {
+            snapshots.remove(start);
+        }
+    }
+
+    let (_, last, mut total) = best?;
+    let mut victims = vec![(dirs[last].0.clone(), dirs[last].1)];
+
+    while total > 0 {
+        let (path, size, _) = &dirs[first_reached[total] as usize];
+        victims.push((path.clone(), *size));
+        total -= size;
+    }
+
+    victims.reverse();
+    Some(victims)
+}
+
+pub(super) struct Plan {
+    strategy: Strategy,
+    disk_size: usize,
+    needed: usize,
+    used: usize,
+    target: usize,
+    victims: Option<Victims>,
+    runner_up: Option<(String, usize)>,
+}
+
+impl Plan {
+    pub(super) fn victims(&self) -> Option<&[(String, usize)]> {
+        self.victims.as_deref()
+    }
+
+    pub(super) fn freed(&self) -> usize {
+        self.victims.iter().flatten().map(|(_, size)| size).sum()
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let available = self.disk_size.saturating_sub(self.used);
+
+        writeln!(f, "strategy: {:?}", self.strategy)?;
+        writeln!(
+            f,
+            "disk {} used {} available {}, need {} so must free {}",
+            self.disk_size, self.used, available, self.needed, self.target
+        )?;
+
+        match &self.victims {
+            None => writeln!(f, "no combination of directories frees enough space"),
+            Some(victims) if victims.is_empty() => writeln!(f, "nothing needs deleting"),
+            Some(victims) => {
+                for (path, size) in victims {
+                    writeln!(f, "  delete {path:50} {size:10}")?;
+                }
+
+                let freed = self.freed();
+                writeln!(
+                    f,
+                    "frees {freed}, {} more than required",
+                    freed - self.target
+                )?;
+
+                if let Some((path, size)) = &self.runner_up {
+                    writeln!(
+                        f,
+                        "largest directory that falls short is {path} with size {size}"
+                    )?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    // A small linear congruential generator, enough to vary the trees.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self, bound: usize) -> usize {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 33) as usize % bound
+        }
+    }
+
+    // Limits on the random trees: how many directories below the root, how
+    // deep, how many subdirectories each, and how big each file.
+    struct Shape {
+        max_dirs: usize,
+        max_depth: usize,
+        max_children: usize,
+        max_file: usize,
+    }
+
+    const SMALL: Shape = Shape {
+        max_dirs: 10,
+        max_depth: 3,
+        max_children: 4,
+        max_file: 50,
+    };
+
+    fn tree(rng: &mut Lcg, shape: &Shape, dirs: &mut usize, depth: usize) -> Entry {
+        let mut entries = BTreeMap::new();
+
+        for i in 0..rng.next(4) {
+            entries.insert(format!("f{i}"), Entry::File(1 + rng.next(shape.max_file)));
+        }
+
+        for i in 0..rng.next(shape.max_children) {
+            if depth < shape.max_depth && *dirs < shape.max_dirs {
+                *dirs += 1;
+                entries.insert(format!("d{i}"), tree(rng, shape, dirs, depth + 1));
+            }
+        }
+
+        Entry::Directory(entries)
+    }
+
+    fn directories(root: &Entry, usage: &DiskUsage) -> Vec<(String, usize)> {
+        root.iter()
+            .filter(|(_, entry)| entry.is_dir())
+            .map(|(path, _)| {
+                let size = usage.get(&path).unwrap();
+                (path, size)
+            })
+            .collect()
+    }
+
+    fn contains(ancestor: &str, path: &str) -> bool {
+        ancestor == "/" || path.starts_with(&format!("{ancestor}/"))
+    }
+
+    fn assert_disjoint(victims: &[(String, usize)]) {
+        for (outer, _) in victims {
+            for (inner, _) in victims {
+                assert!(outer == inner || !contains(outer, inner));
+            }
+        }
+    }
+
+    // Tries every subset of directories.
+    fn brute_force(dirs: &[(String, usize)], target: usize, limit: usize) -> Option<usize> {
+        (0..1u32 << dirs.len())
+            .filter_map(|mask| {
+                let chosen: Vec<&(String, usize)> = (0..dirs.len())
+                    .filter(|i| mask & (1 << i) != 0)
+                    .map(|i| &dirs[i])
+                    .collect();
+
+                let nested = chosen.iter().any(|(outer, _)| {
+                    chosen
+                        .iter()
+                        .any(|(inner, _)| outer != inner && contains(outer, inner))
+                });
+                let total: usize = chosen.iter().map(|(_, size)| size).sum();
+
+                (!nested && total >= target && total <= limit).then_some(total)
+            })
+            .min()
+    }
+
+    #[test]
+    fn minimal_set_matches_brute_force() {
+        let mut rng = Lcg(7);
+
+        for _ in 0..300 {
+            let root = tree(&mut rng, &SMALL, &mut 0, 0);
+            let usage = root.du();
+            let dirs = directories(&root, &usage);
+
+            let total = usage.total();
+            let target = rng.next(total + 1);
+            let limit = target + rng.next(total + 1);
+
+            let expected = brute_force(&dirs, target, limit);
+            let victims = minimal_set(&root, &usage, target, limit);
+            let found = victims
+                .as_ref()
+                .map(|victims| victims.iter().map(|(_, size)| size).sum());
+            assert_eq!(found, expected, "target {target} limit {limit} in {dirs:?}");
+
+            assert_disjoint(victims.as_deref().unwrap_or_default());
+        }
+    }
+
+    // Puzzle-sized trees with targets in the millions, each target the total
+    // of some set of directories so the best answer frees exactly that.
+    #[test]
+    fn minimal_set_at_scale() {
+        let shape = Shape {
+            max_dirs: 190,
+            max_depth: 6,
+            max_children: 6,
+            max_file: 200_000,
+        };
+        let mut rng = Lcg(11);
+
+        for wanted in [500_000, 2_000_000, 5_000_000] {
+            let root = tree(&mut rng, &shape, &mut 0, 0);
+            let usage = root.du();
+            let dirs = directories(&root, &usage);
+
+            let mut chosen: Vec<&str> = Vec::new();
+            let mut target = 0;
+            while target < wanted {
+                let (path, size) = &dirs[1 + rng.next(dirs.len() - 1)];
+                let clashes = chosen
+                    .iter()
+                    .any(|other| contains(other, path) || contains(path, other));
+                if !clashes {
+                    chosen.push(path);
+                    target += size;
+                }
+            }
+
+            let victims = minimal_set(&root, &usage, target, usage.total()).unwrap();
+            let freed: usize = victims.iter().map(|(_, size)| size).sum();
+            assert_eq!(freed, target, "{} directories", dirs.len());
+            assert_disjoint(&victims);
+        }
+    }
+}
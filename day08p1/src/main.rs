@@ -1,7 +1,11 @@
 use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::iter;
+use std::io::{BufReader, BufWriter};
+
+mod forest;
+mod render;
+
+use render::Heatmap;
 
 trait SingleChar {
     fn single_char(&self) -> char;
@@ -23,7 +27,7 @@ impl SingleChar for bool {
     }
 }
 
-fn mprint<T: SingleChar>(matrix: &Vec<Vec<T>>) {
+fn mprint<T: SingleChar>(matrix: &[Vec<T>]) {
     for row in matrix {
         for element in row {
             print!("{}", element.single_char());
@@ -45,64 +49,41 @@ fn main() {
         trees.push(row);
     }
 
-    mprint(&trees);
-
-    let mut visibility: Vec<Vec<bool>> =
-        iter::repeat_with(|| iter::repeat(false).take(trees[0].len()).collect())
-            .take(trees.len())
-            .collect();
-
-    for row in 0..trees.len() {
-        visibility[row][0] = true;
-        let mut min = trees[row][0];
-
-        for col in 1..trees[row].len() {
-            if trees[row][col] > min {
-                visibility[row][col] = true;
-                min = trees[row][col]
-            }
+    let analysis = forest::analyse(&trees);
+
+    if let Some(mode) = args.get(2) {
+        let heatmap = match args[3].as_str() {
+            "height" => Heatmap::heights(&trees),
+            "visibility" => Heatmap::visibility(&analysis),
+            "scenic" => Heatmap::scenic(&analysis),
+            layer => panic!("unknown layer {layer}"),
+        };
+        let pixel_size = args.get(5).map_or(4, |size| size.parse().unwrap());
+
+        match mode.as_str() {
+            "heatmap" => print!("{}", heatmap.ansi()),
+            "pgm" => heatmap
+                .write_pgm(BufWriter::new(File::create(&args[4]).unwrap()), pixel_size)
+                .unwrap(),
+            "ppm" => heatmap
+                .write_ppm(BufWriter::new(File::create(&args[4]).unwrap()), pixel_size)
+                .unwrap(),
+            _ => panic!("unknown mode {mode}"),
         }
 
-        visibility[row][trees[row].len() - 1] = true;
-        let mut min = trees[row][trees[row].len() - 1];
-
-        for col in (0..trees[row].len() - 1).rev() {
-            if trees[row][col] > min {
-                visibility[row][col] = true;
-                min = trees[row][col]
-            }
-        }
+        return;
     }
 
-    for col in 0..trees[0].len() {
-        visibility[0][col] = true;
-        let mut min = trees[0][col];
-
-        for row in 1..trees.len() {
-            if trees[row][col] > min {
-                visibility[row][col] = true;
-                min = trees[row][col]
-            }
-        }
-
-        visibility[trees.len() - 1][col] = true;
-        let mut min = trees[trees.len() - 1][col];
-
-        for row in (0..trees.len() - 1).rev() {
-            if trees[row][col] > min {
-                visibility[row][col] = true;
-                min = trees[row][col]
-            }
-        }
-    }
+    mprint(&trees);
+    let visibility = analysis.map(|sight| sight.is_visible());
 
     println!();
     mprint(&visibility);
 
-    let visible: usize = visibility
-        .iter()
-        .map(|row| row.iter().filter(|&v| *v).count())
-        .sum();
     println!();
-    println!("Total visible: {visible}")
+    println!("Total visible: {}", analysis.visible_count());
+
+    if let Some((row, col, score)) = analysis.best_scenic() {
+        println!("Best scenic score is {score} at ({row}, {col})");
+    }
 }
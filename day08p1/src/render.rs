@@ -0,0 +1,173 @@
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::forest::Analysis;
+
+// Colour stops for the heatmap ramp, from dark blue through green to yellow.
+const RAMP: [(u8, u8, u8); 5] = [
+    (68, 1, 84),
+    (59, 82, 139),
+    (33, 145, 140),
+    (94, 201, 98),
+    (253, 231, 37),
+];
+
+const LEGEND_STEPS: usize = 10;
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Scale {
+    Linear,
+    Log,
+}
+
+pub(super) struct Heatmap {
+    title: &'static str,
+    values: Vec<Vec<usize>>,
+    max: usize,
+    scale: Scale,
+}
+
+impl Heatmap {
+    fn new(title: &'static str, values: Vec<Vec<usize>>, scale: Scale) -> Self {
+        let max = values.iter().flatten().copied().max().unwrap_or(0);
+
+        Self {
+            title,
+            values,
+            max,
+            scale,
+        }
+    }
+
+    pub(super) fn heights(trees: &[Vec<u32>]) -> Self {
+        let values = trees
+            .iter()
+            .map(|row| row.iter().map(|h| *h as usize).collect())
+            .collect();
+        Self::new("tree height", values, Scale::Linear)
+    }
+
+    pub(super) fn visibility(analysis: &Analysis) -> Self {
+        let values = analysis.map(|sight| sight.is_visible() as usize);
+        Self::new("visible from an edge", values, Scale::Linear)
+    }
+
+    pub(super) fn scenic(analysis: &Analysis) -> Self {
+        let values = analysis.map(|sight| sight.scenic_score());
+        Self::new("scenic score", values, Scale::Log)
+    }
+
+    // Position of a value within the range, from 0.0 to 1.0.
+    fn fraction(&self, value: usize) -> f64 {
+        if self.max == 0 {
+            return 0.0;
+        }
+
+        match self.scale {
+            Scale::Linear => value as f64 / self.max as f64,
+            Scale::Log => (value as f64).ln_1p() / (self.max as f64).ln_1p(),
+        }
+    }
+
+    fn value_at(&self, fraction: f64) -> usize {
+        match self.scale {
+            Scale::Linear => (fraction * self.max as f64).round() as usize,
+            Scale::Log => ((self.max as f64).ln_1p() * fraction).exp_m1().round() as usize,
+        }
+    }
+
+    fn grey(&self, value: usize) -> u8 {
+        (self.fraction(value) * 255.0).round() as u8
+    }
+
+    fn colour(&self, value: usize) -> (u8, u8, u8) {
+        let position = self.fraction(value) * (RAMP.len() - 1) as f64;
+        let index = (position.floor() as usize).min(RAMP.len() - 2);
+        let t = position - index as f64;
+
+        let (r0, g0, b0) = RAMP[index];
+        let (r1, g1, b1) = RAMP[index + 1];
+        let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+
+        (lerp(r0, r1), lerp(g0, g1), lerp(b0, b1))
+    }
+
+    fn write_image<W, F>(
+        &self,
+        mut out: W,
+        magic: &str,
+        pixel_size: usize,
+        pixel: F,
+    ) -> io::Result<()>
+    where
+        W: Write,
+        F: Fn(usize) -> Vec<u8>,
+    {
+        let rows = self.values.len();
+        let cols = self.values.first().map_or(0, |row| row.len());
+
+        writeln!(out, "{magic}")?;
+        writeln!(out, "# {}", self.title)?;
+        writeln!(out, "{} {}", cols * pixel_size, rows * pixel_size)?;
+        writeln!(out, "255")?;
+
+        for row in &self.values {
+            let line: Vec<u8> = row
+                .iter()
+                .flat_map(|value| pixel(*value).repeat(pixel_size))
+                .collect();
+
+            for _ in 0..pixel_size {
+                out.write_all(&line)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn write_pgm<W: Write>(&self, out: W, pixel_size: usize) -> io::Result<()> {
+        self.write_image(out, "P5", pixel_size, |value| vec![self.grey(value)])
+    }
+
+    pub(super) fn write_ppm<W: Write>(&self, out: W, pixel_size: usize) -> io::Result<()> {
+        self.write_image(out, "P6", pixel_size, |value| {
+            let (r, g, b) = self.colour(value);
+            vec![r, g, b]
+        })
+    }
+
+    pub(super) fn ansi(&self) -> Ansi<'_> {
+        Ansi { heatmap: self }
+    }
+}
+
+pub(super) struct Ansi<'a> {
+    heatmap: &'a Heatmap,
+}
+
+impl fmt::Display for Ansi<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let heatmap = self.heatmap;
+
+        for row in &heatmap.values {
+            for value in row {
+                let (r, g, b) = heatmap.colour(*value);
+                write!(f, "\x1b[48;2;{r};{g};{b}m  ")?;
+            }
+            writeln!(f, "\x1b[0m")?;
+        }
+
+        writeln!(f)?;
+        write!(f, "{} ({:?}): ", heatmap.title, heatmap.scale)?;
+        let mut steps: Vec<usize> = (0..=LEGEND_STEPS)
+            .map(|step| heatmap.value_at(step as f64 / LEGEND_STEPS as f64))
+            .collect();
+        steps.dedup();
+
+        for value in steps {
+            let (r, g, b) = heatmap.colour(value);
+            write!(f, "\x1b[48;2;{r};{g};{b}m {value} ")?;
+        }
+        writeln!(f, "\x1b[0m")
+    }
+}
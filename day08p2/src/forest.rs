@@ -0,0 +1,151 @@
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct Sight {
+    visible: [bool; 4],
+    distance: [usize; 4],
+}
+
+impl Sight {
+    pub(super) fn visible_from(&self, direction: Direction) -> bool {
+        self.visible[direction as usize]
+    }
+
+    pub(super) fn is_visible(&self) -> bool {
+        Direction::ALL.iter().any(|d| self.visible_from(*d))
+    }
+
+    pub(super) fn distance(&self, direction: Direction) -> usize {
+        self.distance[direction as usize]
+    }
+
+    pub(super) fn scenic_score(&self) -> usize {
+        Direction::ALL.iter().map(|d| self.distance(*d)).product()
+    }
+}
+
+pub(super) struct Analysis {
+    sight: Vec<Vec<Sight>>,
+}
+
+impl Analysis {
+    pub(super) fn sight(&self, row: usize, col: usize) -> Sight {
+        self.sight[row][col]
+    }
+
+    pub(super) fn visible_count(&self) -> usize {
+        self.sight
+            .iter()
+            .flatten()
+            .filter(|sight| sight.is_visible())
+            .count()
+    }
+
+    pub(super) fn best_scenic(&self) -> Option<(usize, usize, usize)> {
+        self.sight
+            .iter()
+            .enumerate()
+            .flat_map(|(row, sights)| {
+                sights
+                    .iter()
+                    .enumerate()
+                    .map(move |(col, sight)| (row, col, sight.scenic_score()))
+            })
+            .max_by_key(|(_, _, score)| *score)
+    }
+
+    pub(super) fn map<T, F>(&self, f: F) -> Vec<Vec<T>>
+    where
+        F: Fn(&Sight) -> T,
+    {
+        self.sight
+            .iter()
+            .map(|row| row.iter().map(&f).collect())
+            .collect()
+    }
+}
+
+// Walks one line of trees looking back towards the edge it started from. The
+// stack holds the positions of trees not yet blocked by a later one, so its
+// heights never increase from bottom to top. After popping everything
+// shorter than the current tree, the top (if any) is the nearest tree at
+// least as tall: that is where the view stops, and it also hides the current
+// tree from that edge. Every position is pushed and popped at most once.
+fn scan<H, R>(len: usize, height: H, mut record: R)
+where
+    H: Fn(usize) -> u32,
+    R: FnMut(usize, bool, usize),
+{
+    let mut stack: Vec<usize> = Vec::new();
+
+    for i in 0..len {
+        let h = height(i);
+
+        while stack.last().is_some_and(|&top| height(top) < h) {
+            stack.pop();
+        }
+
+        match stack.last() {
+            Some(&blocker) => record(i, false, i - blocker),
+            None => record(i, true, i),
+        }
+
+        stack.push(i);
+    }
+}
+
+pub(super) fn analyse(trees: &[Vec<u32>]) -> Analysis {
+    let rows = trees.len();
+    let cols = trees.first().map_or(0, |row| row.len());
+    let mut sight = vec![vec![Sight::default(); cols]; rows];
+
+    for row in 0..rows {
+        scan(
+            cols,
+            |i| trees[row][i],
+            |i, visible, distance| {
+                sight[row][i].visible[Direction::Left as usize] = visible;
+                sight[row][i].distance[Direction::Left as usize] = distance;
+            },
+        );
+        scan(
+            cols,
+            |i| trees[row][cols - 1 - i],
+            |i, visible, distance| {
+                sight[row][cols - 1 - i].visible[Direction::Right as usize] = visible;
+                sight[row][cols - 1 - i].distance[Direction::Right as usize] = distance;
+            },
+        );
+    }
+
+    for col in 0..cols {
+        scan(
+            rows,
+            |i| trees[i][col],
+            |i, visible, distance| {
+                sight[i][col].visible[Direction::Up as usize] = visible;
+                sight[i][col].distance[Direction::Up as usize] = distance;
+            },
+        );
+        scan(
+            rows,
+            |i| trees[rows - 1 - i][col],
+            |i, visible, distance| {
+                sight[rows - 1 - i][col].visible[Direction::Down as usize] = visible;
+                sight[rows - 1 - i][col].distance[Direction::Down as usize] = distance;
+            },
+        );
+    }
+
+    Analysis { sight }
+}
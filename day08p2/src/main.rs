@@ -1,8 +1,14 @@
 use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
+use std::io::{BufReader, BufWriter};
 
-fn mprint(matrix: &Vec<Vec<u32>>) {
+mod forest;
+mod render;
+
+use forest::Direction;
+use render::Heatmap;
+
+fn mprint(matrix: &[Vec<u32>]) {
     for row in matrix {
         for element in row {
             print!("{element}");
@@ -12,46 +18,6 @@ fn mprint(matrix: &Vec<Vec<u32>>) {
     println!();
 }
 
-fn score(row: usize, col: usize, trees: &Vec<Vec<u32>>) -> usize {
-    let mut up = 0;
-    for r in (0..row).rev() {
-        up += 1;
-        if trees[r][col] >= trees[row][col] {
-            break;
-        }
-    }
-
-    let mut down = 0;
-    for r in (row + 1)..trees.len() {
-        down += 1;
-        if trees[r][col] >= trees[row][col] {
-            break;
-        }
-    }
-
-    let mut left = 0;
-    for c in (0..col).rev() {
-        left += 1;
-        if trees[row][c] >= trees[row][col] {
-            break;
-        }
-    }
-
-    let mut right = 0;
-    for c in (col + 1)..trees[col].len() {
-        right += 1;
-        if trees[row][c] >= trees[row][col] {
-            break;
-        }
-    }
-
-    println!(
-        "({row}, {col}) {up} {left} {down} {right} -> {}",
-        up * down * left * right
-    );
-    up * down * left * right
-}
-
 fn main() {
     let args: Vec<String> = std::env::args().collect();
     let input = BufReader::new(File::open(&args[1]).unwrap());
@@ -65,26 +31,50 @@ fn main() {
         trees.push(row);
     }
 
-    mprint(&trees);
+    let analysis = forest::analyse(&trees);
+
+    if let Some(mode) = args.get(2) {
+        let heatmap = match args[3].as_str() {
+            "height" => Heatmap::heights(&trees),
+            "visibility" => Heatmap::visibility(&analysis),
+            "scenic" => Heatmap::scenic(&analysis),
+            layer => panic!("unknown layer {layer}"),
+        };
+        let pixel_size = args.get(5).map_or(4, |size| size.parse().unwrap());
+
+        match mode.as_str() {
+            "heatmap" => print!("{}", heatmap.ansi()),
+            "pgm" => heatmap
+                .write_pgm(BufWriter::new(File::create(&args[4]).unwrap()), pixel_size)
+                .unwrap(),
+            "ppm" => heatmap
+                .write_ppm(BufWriter::new(File::create(&args[4]).unwrap()), pixel_size)
+                .unwrap(),
+            _ => panic!("unknown mode {mode}"),
+        }
 
-    let mut scores = Vec::new();
+        return;
+    }
 
-    for row in 0..trees.len() {
-        let mut score_row = Vec::new();
+    mprint(&trees);
 
-        for col in 0..trees[row].len() {
-            score_row.push(score(row, col, &trees));
+    for (row, cols) in trees.iter().enumerate() {
+        for col in 0..cols.len() {
+            let sight = analysis.sight(row, col);
+            println!(
+                "({row}, {col}) {} {} {} {} -> {}",
+                sight.distance(Direction::Up),
+                sight.distance(Direction::Left),
+                sight.distance(Direction::Down),
+                sight.distance(Direction::Right),
+                sight.scenic_score()
+            );
         }
-
-        scores.push(score_row);
     }
 
     println!();
+    println!("Total visible: {}", analysis.visible_count());
 
-    let max_score = scores
-        .iter()
-        .map(|r| r.iter().max().unwrap())
-        .max()
-        .unwrap();
-    println!("Best score is {max_score}");
+    let (row, col, max_score) = analysis.best_scenic().unwrap();
+    println!("Best score is {max_score} at ({row}, {col})");
 }
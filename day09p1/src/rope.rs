@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use cgmath::{EuclideanSpace, Point2, Point3, Vector2, Vector3};
+
+pub(super) trait Space: EuclideanSpace<Scalar = isize> + Hash + Eq {
+    fn direction(name: &str) -> Option<Self::Diff>;
+}
+
+impl Space for Point2<isize> {
+    fn direction(name: &str) -> Option<Self::Diff> {
+        match name {
+            "U" => Some(Vector2::new(0, 1)),
+            "D" => Some(Vector2::new(0, -1)),
+            "L" => Some(Vector2::new(-1, 0)),
+            "R" => Some(Vector2::new(1, 0)),
+            _ => None,
+        }
+    }
+}
+
+impl Space for Point3<isize> {
+    fn direction(name: &str) -> Option<Self::Diff> {
+        match name {
+            "U" => Some(Vector3::new(0, 1, 0)),
+            "D" => Some(Vector3::new(0, -1, 0)),
+            "L" => Some(Vector3::new(-1, 0, 0)),
+            "R" => Some(Vector3::new(1, 0, 0)),
+            "F" => Some(Vector3::new(0, 0, 1)),
+            "B" => Some(Vector3::new(0, 0, -1)),
+            _ => None,
+        }
+    }
+}
+
+// A knot moves only once it is no longer touching the one ahead of it, and
+// then steps one unit towards it along every axis where they differ.
+fn follow<P: Space>(leader: P, follower: P) -> P {
+    let axes = 0..P::len();
+
+    if axes.clone().all(|i| (leader[i] - follower[i]).abs() <= 1) {
+        return follower;
+    }
+
+    let mut moved = follower;
+    for i in axes {
+        moved[i] += (leader[i] - follower[i]).signum();
+    }
+    moved
+}
+
+pub(super) struct Rope<P: Space> {
+    knots: Vec<P>,
+    visited: Vec<HashSet<P>>,
+}
+
+impl<P: Space> Rope<P> {
+    pub(super) fn new(length: usize) -> Self {
+        assert!(length > 0, "a rope needs at least one knot");
+
+        Self {
+            knots: vec![P::origin(); length],
+            visited: vec![HashSet::from([P::origin()]); length],
+        }
+    }
+
+    pub(super) fn knots(&self) -> &[P] {
+        &self.knots
+    }
+
+    pub(super) fn tail(&self) -> usize {
+        self.knots.len() - 1
+    }
+
+    pub(super) fn step(&mut self, direction: P::Diff) {
+        self.knots[0] = self.knots[0] + direction;
+        self.visited[0].insert(self.knots[0]);
+
+        for knot in 1..self.knots.len() {
+            let moved = follow(self.knots[knot - 1], self.knots[knot]);
+            if moved == self.knots[knot] {
+                break;
+            }
+
+            self.knots[knot] = moved;
+            self.visited[knot].insert(moved);
+        }
+    }
+
+    pub(super) fn visited(&self, knot: usize) -> &HashSet<P> {
+        &self.visited[knot]
+    }
+}
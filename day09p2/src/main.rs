@@ -1,76 +1,81 @@
-use std::collections::HashSet;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
+use std::path::PathBuf;
+use std::time::Duration;
 
-use cgmath::{Point2, Vector2};
+use cgmath::{Point2, Point3};
 use itertools::Itertools;
 
-fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    let input = BufReader::new(File::open(&args[1]).unwrap());
+mod render;
+mod rope;
 
-    let mut rope: Vec<Point2<isize>> = std::iter::repeat_with(|| Point2::new(0, 0))
-        .take(10)
-        .collect();
-    let mut visited = HashSet::from([rope[9].clone()]);
+use render::{PpmFrames, Renderer, Terminal, Viewport};
+use rope::{Rope, Space};
 
-    for knot in &rope {
-        print!("({}, {}) ", knot.x, knot.y);
-    }
-    println!();
+fn format_knot<P: Space>(knot: &P) -> String {
+    format!("({})", (0..P::len()).map(|i| knot[i]).join(", "))
+}
 
-    for line in input.lines() {
-        let line = line.unwrap();
+fn print_knots<P: Space>(rope: &Rope<P>) {
+    println!("{}", rope.knots().iter().map(format_knot).join(" "));
+}
 
-        let (direction, distance) = line.split(' ').collect_tuple().unwrap();
-        let direction = match direction {
-            "U" => Vector2::new(0, 1),
-            "D" => Vector2::new(0, -1),
-            "L" => Vector2::new(-1, 0),
-            "R" => Vector2::new(1, 0),
-            _ => panic!("unknown direction"),
-        };
-        let distance = usize::from_str_radix(distance, 10).unwrap();
+fn simulate<P, F>(lines: &[String], length: usize, mut on_step: F) -> Rope<P>
+where
+    P: Space,
+    F: FnMut(&Rope<P>),
+{
+    let mut rope: Rope<P> = Rope::new(length);
+    on_step(&rope);
 
-        println!();
-        println!("==> {line}");
+    for line in lines {
+        let (direction, distance) = line.split(' ').collect_tuple().unwrap();
+        let direction = P::direction(direction).expect("unknown direction");
+        let distance: usize = distance.parse().unwrap();
 
         for _ in 0..distance {
-            rope[0] += direction;
-
-            for (a, b) in (0..10).tuple_windows() {
-                let v = follow_vector(&rope[a], &rope[b]);
-                rope[b] += v;
-            }
-
-            visited.insert(rope[9].clone());
-
-            for knot in &rope {
-                print!("({}, {}) ", knot.x, knot.y);
-            }
-            println!();
+            rope.step(direction);
+            on_step(&rope);
         }
     }
 
+    rope
+}
+
+fn report<P: Space>(rope: &Rope<P>) {
     println!();
-    println!("Tail visited {} points", visited.len());
+    for knot in 1..rope.knots().len() {
+        println!("Knot {knot} visited {} points", rope.visited(knot).len());
+    }
+    println!("Tail visited {} points", rope.visited(rope.tail()).len());
 }
 
-fn follow_vector(a: &Point2<isize>, b: &Point2<isize>) -> Vector2<isize> {
-    match (a.x - b.x, a.y - b.y) {
-        (dx, dy) if dx == 0 && dy > 1 => Vector2::new(0, 1),
-        (dx, dy) if dx > 0 && dy > 1 => Vector2::new(1, 1),
-        (dx, dy) if dx > 1 && dy > 0 => Vector2::new(1, 1),
-        (dx, dy) if dx > 1 && dy == 0 => Vector2::new(1, 0),
-        (dx, dy) if dx > 0 && dy < -1 => Vector2::new(1, -1),
-        (dx, dy) if dx > 1 && dy < 0 => Vector2::new(1, -1),
-        (dx, dy) if dx == 0 && dy < -1 => Vector2::new(0, -1),
-        (dx, dy) if dx < 0 && dy < -1 => Vector2::new(-1, -1),
-        (dx, dy) if dx < -1 && dy < 0 => Vector2::new(-1, -1),
-        (dx, dy) if dx < -1 && dy == 0 => Vector2::new(-1, 0),
-        (dx, dy) if dx < 0 && dy > 1 => Vector2::new(-1, 1),
-        (dx, dy) if dx < -1 && dy > 0 => Vector2::new(-1, 1),
-        _ => Vector2::new(0, 0),
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let input = BufReader::new(File::open(&args[1]).unwrap());
+    let lines: Vec<String> = input.lines().map(|l| l.unwrap()).collect();
+
+    let length = args.get(2).map_or(10, |n| n.parse().unwrap());
+    let dimensions = args.get(3).map_or("2", String::as_str);
+    let viewport = Viewport::new(60, 30);
+
+    match (dimensions, args.get(4).map(String::as_str)) {
+        ("2", None) => report(&simulate::<Point2<isize>, _>(&lines, length, print_knots)),
+        ("2", Some("play")) => {
+            let delay = args.get(5).map_or(50, |ms| ms.parse().unwrap());
+            let mut renderer = Renderer::new(Terminal::new(Duration::from_millis(delay)), viewport);
+            let rope = simulate(&lines, length, |rope| renderer.draw(rope).unwrap());
+            report(&rope);
+        }
+        ("2", Some("frames")) => {
+            let pixel_size = args.get(6).map_or(8, |size| size.parse().unwrap());
+            let sink = PpmFrames::new(PathBuf::from(&args[5]), pixel_size).unwrap();
+            let mut renderer = Renderer::new(sink, viewport);
+            let rope = simulate(&lines, length, |rope| renderer.draw(rope).unwrap());
+            report(&rope);
+        }
+        ("3", None) => report(&simulate::<Point3<isize>, _>(&lines, length, print_knots)),
+        (dimensions, mode) => panic!("unsupported mode {mode:?} in {dimensions} dimensions"),
     }
 }
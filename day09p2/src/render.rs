@@ -0,0 +1,225 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use cgmath::Point2;
+
+use crate::rope::Rope;
+
+const MARGIN: isize = 3;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Cell {
+    Empty,
+    Start,
+    Trail,
+    Knot(usize),
+}
+
+// A window onto the plane that scrolls to keep the head away from its edges.
+// `corner` is the bottom-left cell; rows are drawn top to bottom.
+pub(super) struct Viewport {
+    width: usize,
+    height: usize,
+    corner: Point2<isize>,
+}
+
+impl Viewport {
+    pub(super) fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            corner: Point2::new(-(width as isize) / 2, -(height as isize) / 2),
+        }
+    }
+
+    fn follow(&mut self, head: Point2<isize>) {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let margin_x = MARGIN.min(width / 2);
+        let margin_y = MARGIN.min(height / 2);
+
+        if head.x < self.corner.x + margin_x {
+            self.corner.x = head.x - margin_x;
+        } else if head.x >= self.corner.x + width - margin_x {
+            self.corner.x = head.x - width + margin_x + 1;
+        }
+
+        if head.y < self.corner.y + margin_y {
+            self.corner.y = head.y - margin_y;
+        } else if head.y >= self.corner.y + height - margin_y {
+            self.corner.y = head.y - height + margin_y + 1;
+        }
+    }
+
+    fn cell(&self, row: usize, col: usize) -> Point2<isize> {
+        Point2::new(
+            self.corner.x + col as isize,
+            self.corner.y + (self.height - 1 - row) as isize,
+        )
+    }
+}
+
+pub(super) struct Frame {
+    number: usize,
+    knots: usize,
+    cells: Vec<Vec<Cell>>,
+}
+
+impl Frame {
+    fn capture(number: usize, rope: &Rope<Point2<isize>>, trail: usize, view: &Viewport) -> Self {
+        let knots = rope.knots();
+        let visited = rope.visited(trail);
+
+        let cells = (0..view.height)
+            .map(|row| {
+                (0..view.width)
+                    .map(|col| {
+                        let point = view.cell(row, col);
+
+                        // Knots nearer the head are drawn on top.
+                        if let Some(knot) = knots.iter().position(|k| *k == point) {
+                            Cell::Knot(knot)
+                        } else if point == Point2::new(0, 0) {
+                            Cell::Start
+                        } else if visited.contains(&point) {
+                            Cell::Trail
+                        } else {
+                            Cell::Empty
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            number,
+            knots: knots.len(),
+            cells,
+        }
+    }
+
+    fn label(&self, cell: Cell) -> char {
+        match cell {
+            Cell::Empty => '.',
+            Cell::Start => 's',
+            Cell::Trail => '#',
+            Cell::Knot(0) => 'H',
+            Cell::Knot(knot) if knot == self.knots - 1 => 'T',
+            Cell::Knot(knot) => char::from_digit((knot % 36) as u32, 36).unwrap(),
+        }
+    }
+
+    fn colour(&self, cell: Cell) -> [u8; 3] {
+        match cell {
+            Cell::Empty => [24, 24, 24],
+            Cell::Start => [40, 90, 200],
+            Cell::Trail => [110, 110, 110],
+            Cell::Knot(knot) => {
+                // Red at the head fading to yellow at the tail.
+                let t = knot as f64 / (self.knots.max(2) - 1) as f64;
+                [230, (60.0 + 170.0 * t) as u8, 40]
+            }
+        }
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.cells {
+            let line: String = row.iter().map(|cell| self.label(*cell)).collect();
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+pub(super) trait Sink {
+    fn frame(&mut self, frame: &Frame) -> io::Result<()>;
+}
+
+pub(super) struct Terminal {
+    delay: Duration,
+}
+
+impl Terminal {
+    pub(super) fn new(delay: Duration) -> Self {
+        Self { delay }
+    }
+}
+
+impl Sink for Terminal {
+    fn frame(&mut self, frame: &Frame) -> io::Result<()> {
+        let mut out = io::stdout().lock();
+        write!(out, "\x1b[2J\x1b[H{frame}step {}", frame.number)?;
+        out.flush()?;
+
+        thread::sleep(self.delay);
+        Ok(())
+    }
+}
+
+pub(super) struct PpmFrames {
+    dir: PathBuf,
+    pixel_size: usize,
+}
+
+impl PpmFrames {
+    pub(super) fn new(dir: PathBuf, pixel_size: usize) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, pixel_size })
+    }
+}
+
+impl Sink for PpmFrames {
+    fn frame(&mut self, frame: &Frame) -> io::Result<()> {
+        let path = self.dir.join(format!("frame_{:06}.ppm", frame.number));
+        let mut out = BufWriter::new(File::create(path)?);
+
+        let rows = frame.cells.len();
+        let cols = frame.cells.first().map_or(0, |row| row.len());
+        let size = self.pixel_size;
+
+        write!(out, "P6\n{} {}\n255\n", cols * size, rows * size)?;
+
+        for row in &frame.cells {
+            let line: Vec<u8> = row
+                .iter()
+                .flat_map(|cell| frame.colour(*cell).repeat(size))
+                .collect();
+
+            for _ in 0..size {
+                out.write_all(&line)?;
+            }
+        }
+
+        out.flush()
+    }
+}
+
+pub(super) struct Renderer<S: Sink> {
+    sink: S,
+    viewport: Viewport,
+    frames: usize,
+}
+
+impl<S: Sink> Renderer<S> {
+    pub(super) fn new(sink: S, viewport: Viewport) -> Self {
+        Self {
+            sink,
+            viewport,
+            frames: 0,
+        }
+    }
+
+    pub(super) fn draw(&mut self, rope: &Rope<Point2<isize>>) -> io::Result<()> {
+        self.viewport.follow(rope.knots()[0]);
+
+        let frame = Frame::capture(self.frames, rope, rope.tail(), &self.viewport);
+        self.frames += 1;
+
+        self.sink.frame(&frame)
+    }
+}
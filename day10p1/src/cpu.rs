@@ -0,0 +1,307 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Register {
+    X,
+    A,
+    B,
+    C,
+    D,
+}
+
+impl Register {
+    pub(super) const ALL: [Register; 5] = [Self::X, Self::A, Self::B, Self::C, Self::D];
+}
+
+impl FromStr for Register {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "x" => Ok(Self::X),
+            "a" => Ok(Self::A),
+            "b" => Ok(Self::B),
+            "c" => Ok(Self::C),
+            "d" => Ok(Self::D),
+            _ => Err(anyhow!("unknown register {s:?}")),
+        }
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::X => "x",
+            Self::A => "a",
+            Self::B => "b",
+            Self::C => "c",
+            Self::D => "d",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Registers {
+    values: [isize; Register::ALL.len()],
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        let mut registers = Self {
+            values: [0; Register::ALL.len()],
+        };
+        registers[Register::X] = 1;
+        registers
+    }
+}
+
+impl Index<Register> for Registers {
+    type Output = isize;
+
+    fn index(&self, register: Register) -> &isize {
+        &self.values[register as usize]
+    }
+}
+
+impl IndexMut<Register> for Registers {
+    fn index_mut(&mut self, register: Register) -> &mut isize {
+        &mut self.values[register as usize]
+    }
+}
+
+impl fmt::Display for Registers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, register) in Register::ALL.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{register}={}", self[*register])?;
+        }
+        Ok(())
+    }
+}
+
+// What the CPU does once an instruction has finished executing.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Flow {
+    Next,
+    Jump(isize),
+    Halt,
+}
+
+// Anything the CPU can run: it only needs to know how long an instruction
+// takes and what it does to the registers when it completes.
+pub(super) trait InstructionSet: Copy + fmt::Debug {
+    fn cycles(&self) -> usize;
+    fn execute(&self, registers: &mut Registers) -> Flow;
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Operand {
+    Register(Register),
+    Immediate(isize),
+}
+
+impl Operand {
+    fn value(&self, registers: &Registers) -> isize {
+        match self {
+            Self::Register(register) => registers[*register],
+            Self::Immediate(value) => *value,
+        }
+    }
+}
+
+impl FromStr for Operand {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.parse() {
+            Ok(value) => Ok(Self::Immediate(value)),
+            Err(_) => Ok(Self::Register(s.parse()?)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Instruction {
+    Noop,
+    AddX(isize),
+    Set(Register, Operand),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Jmp(isize),
+    Jz(Register, isize),
+    Jnz(Register, isize),
+    Halt,
+}
+
+impl InstructionSet for Instruction {
+    fn cycles(&self) -> usize {
+        match self {
+            Self::Noop | Self::Set(..) | Self::Halt => 1,
+            Self::AddX(_) | Self::Add(..) | Self::Jmp(_) | Self::Jz(..) | Self::Jnz(..) => 2,
+            Self::Mul(..) => 4,
+        }
+    }
+
+    fn execute(&self, registers: &mut Registers) -> Flow {
+        match *self {
+            Self::Noop => (),
+            Self::AddX(value) => registers[Register::X] += value,
+            Self::Set(register, operand) => registers[register] = operand.value(registers),
+            Self::Add(register, operand) => registers[register] += operand.value(registers),
+            Self::Mul(register, operand) => registers[register] *= operand.value(registers),
+            Self::Jmp(offset) => return Flow::Jump(offset),
+            Self::Jz(register, offset) if registers[register] == 0 => return Flow::Jump(offset),
+            Self::Jnz(register, offset) if registers[register] != 0 => return Flow::Jump(offset),
+            Self::Jz(..) | Self::Jnz(..) => (),
+            Self::Halt => return Flow::Halt,
+        }
+
+        Flow::Next
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut words = s.split_whitespace();
+        let opcode = words.next().ok_or_else(|| anyhow!("empty instruction"))?;
+        let mut operand = || {
+            words
+                .next()
+                .ok_or_else(|| anyhow!("missing operand for {opcode}"))
+        };
+
+        let instruction = match opcode {
+            "noop" => Self::Noop,
+            "addx" => Self::AddX(operand()?.parse()?),
+            "set" => Self::Set(operand()?.parse()?, operand()?.parse()?),
+            "add" => Self::Add(operand()?.parse()?, operand()?.parse()?),
+            "mul" => Self::Mul(operand()?.parse()?, operand()?.parse()?),
+            "jmp" => Self::Jmp(operand()?.parse()?),
+            "jz" => Self::Jz(operand()?.parse()?, operand()?.parse()?),
+            "jnz" => Self::Jnz(operand()?.parse()?, operand()?.parse()?),
+            "halt" => Self::Halt,
+            _ => return Err(anyhow!("unknown instruction")),
+        };
+
+        if words.next().is_some() {
+            return Err(anyhow!("too many operands in {s:?}"));
+        }
+
+        Ok(instruction)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Probe {
+    pub(super) cycle: usize,
+    pub(super) x: isize,
+    pub(super) strength: isize,
+}
+
+pub(super) struct Cpu<I: InstructionSet = Instruction> {
+    program: Vec<I>,
+    pc: usize,
+    current_instruction: Option<I>,
+    current_completes: usize,
+    halted: bool,
+
+    cycle: usize,
+    registers: Registers,
+
+    probe_cycles: Vec<usize>,
+    probes: Vec<Probe>,
+}
+
+impl<I: InstructionSet> Cpu<I> {
+    pub(super) fn new<P: IntoIterator<Item = I>>(program: P) -> Self {
+        Self {
+            program: program.into_iter().collect(),
+            pc: 0,
+            current_instruction: None,
+            current_completes: 0,
+            halted: false,
+
+            cycle: 0,
+            registers: Registers::default(),
+
+            probe_cycles: Vec::new(),
+            probes: Vec::new(),
+        }
+    }
+
+    // Cycles (counting from 1) during which to record the value of X.
+    pub(super) fn with_probes<C: IntoIterator<Item = usize>>(mut self, cycles: C) -> Self {
+        self.probe_cycles = cycles.into_iter().collect();
+        self.probe_cycles.sort_unstable();
+        self
+    }
+
+    // Fetches the next instruction if the previous one has completed.
+    // Returns false once the program has halted or run off either end.
+    fn fetch(&mut self) -> bool {
+        if self.current_instruction.is_none() {
+            if self.halted {
+                return false;
+            }
+
+            if let Some(insn) = self.program.get(self.pc).copied() {
+                self.current_completes = self.cycle + insn.cycles();
+                self.current_instruction = Some(insn);
+            } else {
+                self.halted = true;
+                return false;
+            }
+        }
+
+        true
+    }
+
+    pub(super) fn tick(&mut self) -> bool {
+        if !self.fetch() {
+            return false;
+        }
+
+        let x = self.registers[Register::X];
+        self.cycle += 1;
+
+        if self.probe_cycles.binary_search(&self.cycle).is_ok() {
+            self.probes.push(Probe {
+                cycle: self.cycle,
+                x,
+                strength: self.cycle as isize * x,
+            });
+        }
+
+        if self.cycle == self.current_completes {
+            let insn = self.current_instruction.take().unwrap();
+
+            match insn.execute(&mut self.registers) {
+                Flow::Next => self.pc += 1,
+                Flow::Jump(offset) => match self.pc.checked_add_signed(offset) {
+                    Some(pc) => self.pc = pc,
+                    None => self.halted = true,
+                },
+                Flow::Halt => self.halted = true,
+            }
+        }
+
+        true
+    }
+
+    pub(super) fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    pub(super) fn probes(&self) -> &[Probe] {
+        &self.probes
+    }
+}
@@ -2,54 +2,33 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 
+mod cpu;
+
+use cpu::{Cpu, Instruction};
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
     let input = BufReader::new(File::open(&args[1]).unwrap());
 
-    let mut x: isize = 1;
-    let mut cycle: usize = 0;
-    let mut sum = 0;
-
-    for line in input.lines() {
-        let line = line.unwrap();
-
-        if line == "noop" {
-            cycle += 1;
-
-            if cycle % 40 == 20 {
-                println!(
-                    "At cycle {cycle}, X = {x}, signal strength = {}",
-                    cycle as isize * x
-                );
-                sum += cycle as isize * x;
-            }
-        } else {
-            let (_, value) = line.split_once(' ').unwrap();
-
-            cycle += 1;
-            if cycle % 40 == 20 {
-                println!(
-                    "At cycle {cycle}, X = {x}, signal strength = {}",
-                    cycle as isize * x
-                );
-                sum += cycle as isize * x;
-            }
-            cycle += 1;
-            if cycle % 40 == 20 {
-                println!(
-                    "At cycle {cycle}, X = {x}, signal strength = {}",
-                    cycle as isize * x
-                );
-                sum += cycle as isize * x;
-            }
-
-            x += value.parse::<isize>().unwrap();
-        }
+    let instructions = input
+        .lines()
+        .map(|l| l.unwrap().parse::<Instruction>().unwrap());
 
-        if cycle == 220 {
+    let mut cpu = Cpu::new(instructions).with_probes((20..=220).step_by(40));
+
+    while cpu.cycle() < 220 {
+        if !cpu.tick() {
             break;
         }
     }
 
+    for probe in cpu.probes() {
+        println!(
+            "At cycle {}, X = {}, signal strength = {}",
+            probe.cycle, probe.x, probe.strength
+        );
+    }
+
+    let sum: isize = cpu.probes().iter().map(|probe| probe.strength).sum();
     println!("Sum of signal strengths = {sum}")
 }
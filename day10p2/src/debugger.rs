@@ -0,0 +1,179 @@
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+
+use crate::cpu::{Cpu, InstructionSet, Register};
+
+const HELP: &str = "\
+commands:
+  s, step [n]         run n cycles (default 1)
+  c, continue         run until a breakpoint is hit or the program halts
+  b, break cycle <n>  stop once cycle n has completed
+  b, break <reg>=<v>  stop when register reg changes to v
+  l, list             list breakpoints
+  d, delete <i>       delete breakpoint i
+  r, regs             dump registers
+  h, help             show this message
+  q, quit             leave the debugger";
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Breakpoint {
+    Cycle(usize),
+    Register(Register, isize),
+}
+
+impl FromStr for Breakpoint {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(cycle) = s.strip_prefix("cycle") {
+            Ok(Self::Cycle(cycle.trim().parse()?))
+        } else if let Some((register, value)) = s.split_once('=') {
+            Ok(Self::Register(
+                register.trim().parse()?,
+                value.trim().parse()?,
+            ))
+        } else {
+            Err(anyhow!("cannot parse breakpoint {s:?}"))
+        }
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle(cycle) => write!(f, "cycle {cycle}"),
+            Self::Register(register, value) => write!(f, "{register}={value}"),
+        }
+    }
+}
+
+pub(super) struct Debugger<I: InstructionSet> {
+    cpu: Cpu<I>,
+    breakpoints: Vec<Breakpoint>,
+}
+
+impl<I: InstructionSet + fmt::Display> Debugger<I> {
+    pub(super) fn new(cpu: Cpu<I>) -> Self {
+        Self {
+            cpu,
+            breakpoints: Vec::new(),
+        }
+    }
+
+    // Runs one cycle and reports the first breakpoint it triggered, if any.
+    fn step(&mut self) -> Option<Option<Breakpoint>> {
+        let before = self.cpu.registers().clone();
+
+        if !self.cpu.tick() {
+            return None;
+        }
+
+        let after = self.cpu.registers();
+        let hit = self.breakpoints.iter().copied().find(|bp| match *bp {
+            Breakpoint::Cycle(cycle) => self.cpu.cycle() == cycle,
+            Breakpoint::Register(register, value) => {
+                after[register] == value && before[register] != value
+            }
+        });
+
+        Some(hit)
+    }
+
+    fn state<W: Write>(&self, out: &mut W) -> Result<()> {
+        match self.cpu.next_instruction() {
+            Some(insn) => writeln!(
+                out,
+                "cycle {} pc {} next {} | {}",
+                self.cpu.cycle(),
+                self.cpu.pc(),
+                insn,
+                self.cpu.registers()
+            )?,
+            None => writeln!(
+                out,
+                "cycle {} halted | {}",
+                self.cpu.cycle(),
+                self.cpu.registers()
+            )?,
+        }
+        Ok(())
+    }
+
+    fn command<W: Write>(&mut self, line: &str, out: &mut W) -> Result<bool> {
+        let (command, arg) = line.split_once(' ').unwrap_or((line, ""));
+        let arg = arg.trim();
+
+        match command {
+            "" => (),
+            "s" | "step" => {
+                let count = if arg.is_empty() { 1 } else { arg.parse()? };
+
+                for _ in 0..count {
+                    match self.step() {
+                        None => break,
+                        Some(Some(bp)) => {
+                            writeln!(out, "breakpoint {bp} hit")?;
+                            break;
+                        }
+                        Some(None) => (),
+                    }
+                }
+                self.state(out)?;
+            }
+            "c" | "continue" => {
+                while let Some(hit) = self.step() {
+                    if let Some(bp) = hit {
+                        writeln!(out, "breakpoint {bp} hit")?;
+                        break;
+                    }
+                }
+                self.state(out)?;
+            }
+            "b" | "break" => {
+                let bp: Breakpoint = arg.parse()?;
+                self.breakpoints.push(bp);
+                writeln!(out, "breakpoint {} at {bp}", self.breakpoints.len() - 1)?;
+            }
+            "l" | "list" => {
+                for (i, bp) in self.breakpoints.iter().enumerate() {
+                    writeln!(out, "{i}: {bp}")?;
+                }
+            }
+            "d" | "delete" => {
+                let index: usize = arg.parse()?;
+                if index >= self.breakpoints.len() {
+                    return Err(anyhow!("no breakpoint {index}"));
+                }
+                self.breakpoints.remove(index);
+            }
+            "r" | "regs" => self.state(out)?,
+            "h" | "help" => writeln!(out, "{HELP}")?,
+            "q" | "quit" => return Ok(false),
+            _ => return Err(anyhow!("unknown command {command:?}, try help")),
+        }
+
+        Ok(true)
+    }
+
+    pub(super) fn run<R: BufRead, W: Write>(&mut self, input: R, mut out: W) -> Result<()> {
+        self.state(&mut out)?;
+        write!(out, "(dbg) ")?;
+        out.flush()?;
+
+        for line in input.lines() {
+            match self.command(line?.trim(), &mut out) {
+                Ok(true) => (),
+                Ok(false) => break,
+                Err(err) => writeln!(out, "error: {err}")?,
+            }
+
+            write!(out, "(dbg) ")?;
+            out.flush()?;
+        }
+
+        Ok(())
+    }
+}
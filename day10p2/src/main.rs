@@ -1,20 +1,62 @@
 use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
+use std::io::{BufReader, BufWriter};
 
 mod cpu;
+mod debugger;
+mod ocr;
+mod trace;
 
-use cpu::Cpu;
+use cpu::{Cpu, Instruction};
+use debugger::Debugger;
+use trace::Format;
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
     let input = BufReader::new(File::open(&args[1]).unwrap());
 
-    let instructions = input.lines().map(|l| l.unwrap().parse().unwrap());
+    let program: Vec<Instruction> = input.lines().map(|l| l.unwrap().parse().unwrap()).collect();
 
-    let mut cpu = Cpu::new(instructions);
+    if args.get(2).map(String::as_str) == Some("disasm") {
+        for (address, insn) in program.iter().enumerate() {
+            println!("{address:4}  {insn}");
+        }
+        return;
+    }
+
+    let mut cpu = Cpu::new(program).with_probes((20..=220).step_by(40));
+
+    match args.get(2).map(String::as_str) {
+        Some("debug") => {
+            let stdin = std::io::stdin();
+            Debugger::new(cpu)
+                .run(stdin.lock(), std::io::stdout())
+                .unwrap();
+            return;
+        }
+        Some("trace") => {
+            let format = args.get(3).map_or(Ok(Format::Csv), |f| f.parse()).unwrap();
+            let cycles = cpu.width() * cpu.height();
+
+            match args.get(4) {
+                Some(path) => {
+                    let out = BufWriter::new(File::create(path).unwrap());
+                    trace::write(&mut cpu, cycles, format, out).unwrap();
+                }
+                None => trace::write(&mut cpu, cycles, format, std::io::stdout().lock()).unwrap(),
+            }
+            return;
+        }
+        _ => (),
+    }
 
-    while cpu.cycle() < 240 {
+    if let Some(width) = args.get(2) {
+        let height = args.get(3).map_or(6, |h| h.parse().unwrap());
+        let sprite_width = args.get(4).map_or(3, |w| w.parse().unwrap());
+        cpu = cpu.with_display(width.parse().unwrap(), height, sprite_width);
+    }
+
+    while cpu.cycle() < cpu.width() * cpu.height() {
         if !cpu.tick() {
             break;
         }
@@ -22,10 +64,25 @@ fn main() {
         println!("X={:4} at cycle {:3}", cpu.x(), cpu.cycle());
     }
 
-    for (i, pixel) in cpu.display().iter().enumerate() {
-        print!("{}", if *pixel { '#' } else { '.' });
-        if i % 40 == 39 {
-            println!();
-        }
+    println!();
+    for probe in cpu.probes() {
+        println!(
+            "At cycle {}, X = {}, signal strength = {}",
+            probe.cycle, probe.x, probe.strength
+        );
+    }
+    let sum: isize = cpu.probes().iter().map(|probe| probe.strength).sum();
+    println!("Sum of signal strengths = {sum}");
+
+    println!();
+    for row in cpu.display().chunks(cpu.width()) {
+        let line: String = row.iter().map(|p| if *p { '#' } else { '.' }).collect();
+        println!("{line}");
+    }
+
+    println!();
+    match ocr::decode(cpu.display(), cpu.width()) {
+        Ok(letters) => println!("Display reads {letters}"),
+        Err(err) => println!("Cannot read display: {err}"),
     }
 }
@@ -0,0 +1,116 @@
+use std::error::Error;
+use std::fmt;
+
+const GLYPH_WIDTH: usize = 4;
+const GLYPH_HEIGHT: usize = 6;
+
+// Each glyph is followed by one blank column.
+const GLYPH_STRIDE: usize = GLYPH_WIDTH + 1;
+
+// The capital letters the puzzle is known to use, drawn row by row.
+const FONT: [(char, &str); 17] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+#[derive(Debug)]
+pub(super) enum OcrError {
+    Empty,
+    Height(usize),
+    Unrecognised {
+        positions: Vec<usize>,
+        partial: String,
+    },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "display has no columns"),
+            Self::Height(height) => write!(
+                f,
+                "display is {height} rows high, glyphs need {GLYPH_HEIGHT}"
+            ),
+            Self::Unrecognised { positions, partial } => {
+                let positions: Vec<String> = positions.iter().map(|p| p.to_string()).collect();
+                write!(
+                    f,
+                    "unrecognised glyphs at positions {} (read {partial:?})",
+                    positions.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl Error for OcrError {}
+
+fn pattern(art: &str) -> u32 {
+    art.chars()
+        .fold(0, |bits, pixel| (bits << 1) | (pixel == '#') as u32)
+}
+
+fn glyph(display: &[bool], width: usize, index: usize) -> u32 {
+    let mut bits = 0;
+
+    for row in 0..GLYPH_HEIGHT {
+        for col in 0..GLYPH_WIDTH {
+            let x = index * GLYPH_STRIDE + col;
+            let lit = x < width && display[row * width + x];
+            bits = (bits << 1) | lit as u32;
+        }
+    }
+
+    bits
+}
+
+// Reads the letters off a display `width` pixels wide, stored row by row.
+pub(super) fn decode(display: &[bool], width: usize) -> Result<String, OcrError> {
+    if width == 0 {
+        return Err(OcrError::Empty);
+    }
+
+    let height = display.len() / width;
+    if height != GLYPH_HEIGHT {
+        return Err(OcrError::Height(height));
+    }
+
+    let mut text = String::new();
+    let mut unrecognised = Vec::new();
+
+    for index in 0..width.div_ceil(GLYPH_STRIDE) {
+        let bits = glyph(display, width, index);
+
+        match FONT.iter().find(|(_, art)| pattern(art) == bits) {
+            Some((letter, _)) => text.push(*letter),
+            None => {
+                text.push('?');
+                unrecognised.push(index);
+            }
+        }
+    }
+
+    if unrecognised.is_empty() {
+        Ok(text)
+    } else {
+        Err(OcrError::Unrecognised {
+            positions: unrecognised,
+            partial: text,
+        })
+    }
+}
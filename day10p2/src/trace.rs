@@ -0,0 +1,101 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+
+use crate::cpu::{Cpu, InstructionSet};
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Format {
+    Csv,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err(anyhow!("unknown trace format {s:?}")),
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut quoted = String::from('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            _ => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Runs the CPU for up to `cycles` cycles, writing one record per cycle. The
+// pixel column is empty (or null) once the beam has left the display.
+pub(super) fn write<I, W>(
+    cpu: &mut Cpu<I>,
+    cycles: usize,
+    format: Format,
+    mut out: W,
+) -> io::Result<()>
+where
+    I: InstructionSet + fmt::Display,
+    W: Write,
+{
+    match format {
+        Format::Csv => writeln!(out, "cycle,instruction,x,pixel,lit")?,
+        Format::Json => writeln!(out, "[")?,
+    }
+
+    let mut first = true;
+
+    while cpu.cycle() < cycles {
+        let Some(tick) = cpu.step() else {
+            break;
+        };
+
+        match format {
+            Format::Csv => writeln!(
+                out,
+                "{},{},{},{},{}",
+                tick.cycle,
+                tick.instruction,
+                tick.x,
+                tick.pixel.map_or(String::new(), |p| p.to_string()),
+                tick.lit
+            )?,
+            Format::Json => {
+                if !first {
+                    writeln!(out, ",")?;
+                }
+                write!(
+                    out,
+                    "  {{\"cycle\": {}, \"instruction\": {}, \"x\": {}, \"pixel\": {}, \"lit\": {}}}",
+                    tick.cycle,
+                    json_string(&tick.instruction.to_string()),
+                    tick.x,
+                    tick.pixel.map_or("null".to_owned(), |p| p.to_string()),
+                    tick.lit
+                )?;
+            }
+        }
+
+        first = false;
+    }
+
+    if let Format::Json = format {
+        if !first {
+            writeln!(out)?;
+        }
+        writeln!(out, "]")?;
+    }
+
+    out.flush()
+}
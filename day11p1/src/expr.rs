@@ -0,0 +1,190 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Op {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+}
+
+impl Op {
+    fn symbol(&self) -> char {
+        match self {
+            Self::Add => '+',
+            Self::Subtract => '-',
+            Self::Multiply => '*',
+            Self::Divide => '/',
+            Self::Remainder => '%',
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum Expr {
+    Old,
+    Number(usize),
+    Binary(Box<Expr>, Op, Box<Expr>),
+}
+
+#[derive(Debug)]
+pub(super) enum ExprError {
+    Syntax { offset: usize, message: String },
+    Overflow,
+    Negative,
+    DivideByZero,
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { offset, message } => write!(f, "at offset {offset}: {message}"),
+            Self::Overflow => write!(f, "worry level overflowed"),
+            Self::Negative => write!(f, "worry level went negative"),
+            Self::DivideByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+impl Expr {
+    pub(super) fn eval(&self, old: usize) -> Result<usize, ExprError> {
+        match self {
+            Self::Old => Ok(old),
+            Self::Number(n) => Ok(*n),
+            Self::Binary(lhs, op, rhs) => {
+                let (a, b) = (lhs.eval(old)?, rhs.eval(old)?);
+
+                match op {
+                    Op::Add => a.checked_add(b).ok_or(ExprError::Overflow),
+                    Op::Subtract => a.checked_sub(b).ok_or(ExprError::Negative),
+                    Op::Multiply => a.checked_mul(b).ok_or(ExprError::Overflow),
+                    Op::Divide => a.checked_div(b).ok_or(ExprError::DivideByZero),
+                    Op::Remainder => a.checked_rem(b).ok_or(ExprError::DivideByZero),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Old => write!(f, "old"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Binary(lhs, op, rhs) => write!(f, "({lhs} {} {rhs})", op.symbol()),
+        }
+    }
+}
+
+// Recursive descent over the usual precedence levels:
+//
+//   expr   := term (('+' | '-') term)*
+//   term   := factor (('*' | '/' | '%') factor)*
+//   factor := 'old' | number | '(' expr ')'
+struct Parser<'a> {
+    source: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            chars: source.char_indices().peekable(),
+        }
+    }
+
+    fn error<T>(&mut self, message: &str) -> Result<T, ExprError> {
+        let offset = self.peek().map_or(self.source.len(), |(i, _)| i);
+        Err(ExprError::Syntax {
+            offset,
+            message: message.to_owned(),
+        })
+    }
+
+    fn peek(&mut self) -> Option<(usize, char)> {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    fn operator(&mut self, ops: &[Op]) -> Option<Op> {
+        let (_, c) = self.peek()?;
+        let op = ops.iter().find(|op| op.symbol() == c).copied()?;
+        self.chars.next();
+        Some(op)
+    }
+
+    fn expr(&mut self) -> Result<Expr, ExprError> {
+        let mut lhs = self.term()?;
+
+        while let Some(op) = self.operator(&[Op::Add, Op::Subtract]) {
+            let rhs = self.term()?;
+            lhs = Expr::Binary(Box::new(lhs), op, Box::new(rhs));
+        }
+
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Expr, ExprError> {
+        let mut lhs = self.factor()?;
+
+        while let Some(op) = self.operator(&[Op::Multiply, Op::Divide, Op::Remainder]) {
+            let rhs = self.factor()?;
+            lhs = Expr::Binary(Box::new(lhs), op, Box::new(rhs));
+        }
+
+        Ok(lhs)
+    }
+
+    fn factor(&mut self) -> Result<Expr, ExprError> {
+        match self.peek() {
+            Some((_, '(')) => {
+                self.chars.next();
+                let inner = self.expr()?;
+
+                if let Some((_, ')')) = self.peek() {
+                    self.chars.next();
+                    Ok(inner)
+                } else {
+                    self.error("expected ')'")
+                }
+            }
+            Some((start, c)) if c.is_ascii_digit() => {
+                let mut end = start;
+                while let Some((i, _)) = self.chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                    end = i + 1;
+                }
+
+                match self.source[start..end].parse() {
+                    Ok(n) => Ok(Expr::Number(n)),
+                    Err(_) => self.error("number too large"),
+                }
+            }
+            Some((start, _)) if self.source[start..].starts_with("old") => {
+                for _ in 0..3 {
+                    self.chars.next();
+                }
+                Ok(Expr::Old)
+            }
+            _ => self.error("expected 'old', a number or '('"),
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ExprError;
+
+    fn from_str(s: &str) -> Result<Self, ExprError> {
+        let mut parser = Parser::new(s);
+        let expr = parser.expr()?;
+
+        if parser.peek().is_some() {
+            return parser.error("unexpected trailing input");
+        }
+
+        Ok(expr)
+    }
+}
@@ -1,5 +1,10 @@
 use itertools::Itertools;
 
+mod expr;
+mod notes;
+
+use expr::Expr;
+
 #[derive(Debug)]
 struct Item(usize);
 
@@ -10,63 +15,13 @@ impl Item {
 }
 
 #[derive(Debug)]
-enum Operand {
-    Old,
-    Number(usize),
-}
-
-impl From<&str> for Operand {
-    fn from(s: &str) -> Self {
-        if s == "old" {
-            Self::Old
-        } else {
-            Self::Number(s.parse().unwrap())
-        }
-    }
-}
-
-#[derive(Debug)]
-enum Operator {
-    Add,
-    Multiply,
-}
-
-impl From<&str> for Operator {
-    fn from(s: &str) -> Self {
-        match s {
-            "+" => Operator::Add,
-            "*" => Operator::Multiply,
-            _ => panic!("unknown operator"),
-        }
-    }
-}
-
-#[derive(Debug)]
-struct Operation {
-    op: Operator,
-    arg: Operand,
-}
-
-impl From<&str> for Operation {
-    fn from(s: &str) -> Self {
-        let (op, arg) = s.split_once(' ').unwrap();
-        let op = Operator::from(op);
-        let arg = Operand::from(arg);
-
-        Self { op, arg }
-    }
-}
+struct Operation(Expr);
 
 impl Operation {
     fn perform(&self, item: &mut Item) {
-        let arg = match self.arg {
-            Operand::Old => item.0,
-            Operand::Number(n) => n,
-        };
-
-        item.0 = match self.op {
-            Operator::Add => item.0 + arg,
-            Operator::Multiply => item.0 * arg,
+        item.0 = match self.0.eval(item.0) {
+            Ok(value) => value,
+            Err(err) => panic!("cannot evaluate {}: {err}", self.0),
         };
     }
 }
@@ -80,7 +35,7 @@ struct TargetTest {
 
 impl TargetTest {
     fn choose(&self, item: &Item) -> usize {
-        if item.0 % self.divisor == 0 {
+        if item.0.is_multiple_of(self.divisor) {
             self.true_target
         } else {
             self.false_target
@@ -120,42 +75,10 @@ fn main() {
     let args: Vec<String> = std::env::args().collect();
     let input = std::fs::read_to_string(&args[1]).unwrap();
 
-    let mut monkeys = Vec::new();
-
-    for monkey in input.split_terminator("\n\n") {
-        let lines: Vec<&str> = monkey.split_terminator('\n').collect();
-
-        let (_, items) = lines[1].rsplit_once(": ").unwrap();
-        let items: Vec<Item> = items
-            .split(", ")
-            .map(|v| Item(v.parse().unwrap()))
-            .collect();
-
-        let (_, operation) = lines[2].split_once(" = old ").unwrap();
-        let operation = Operation::from(operation);
-
-        let (_, divisor) = lines[3].rsplit_once(' ').unwrap();
-        let divisor: usize = divisor.parse().unwrap();
-
-        let (_, true_target) = lines[4].rsplit_once(' ').unwrap();
-        let true_target: usize = true_target.parse().unwrap();
-
-        let (_, false_target) = lines[5].rsplit_once(' ').unwrap();
-        let false_target: usize = false_target.parse().unwrap();
-
-        let target = TargetTest {
-            divisor,
-            true_target,
-            false_target,
-        };
-
-        monkeys.push(Monkey {
-            items,
-            operation,
-            target,
-            inspections: 0,
-        });
-    }
+    let mut monkeys = match notes::parse(&input) {
+        Ok(monkeys) => monkeys,
+        Err(err) => panic!("cannot read notes: {err}"),
+    };
 
     for round in 1..=20 {
         for monkey in 0..monkeys.len() {
@@ -165,14 +88,10 @@ fn main() {
         }
 
         println!("After round {round}, the monkeys are holding items with these worry levels:");
-        for monkey in 0..monkeys.len() {
+        for (i, monkey) in monkeys.iter().enumerate() {
             println!(
-                "Monkey {monkey}: {}",
-                monkeys[monkey]
-                    .items
-                    .iter()
-                    .map(|i| format!("{}", i.0))
-                    .join(", ")
+                "Monkey {i}: {}",
+                monkey.items.iter().map(|i| format!("{}", i.0)).join(", ")
             );
         }
     }
@@ -0,0 +1,225 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{Item, Monkey, Operation, TargetTest};
+
+#[derive(Debug)]
+pub(super) struct NotesError {
+    line: usize,
+    message: String,
+}
+
+impl NotesError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for NotesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for NotesError {}
+
+// A field seen while reading one monkey's notes, along with the line it came
+// from so later checks can point at it.
+struct Field<T> {
+    line: usize,
+    value: T,
+}
+
+#[derive(Default)]
+struct Notes {
+    items: Option<Field<Vec<Item>>>,
+    operation: Option<Field<Operation>>,
+    divisor: Option<Field<usize>>,
+    true_target: Option<Field<usize>>,
+    false_target: Option<Field<usize>>,
+}
+
+fn number<T: std::str::FromStr>(line: usize, s: &str, what: &str) -> Result<T, NotesError> {
+    s.trim()
+        .parse()
+        .map_err(|_| NotesError::new(line, format!("expected {what}, found {:?}", s.trim())))
+}
+
+fn set<T>(slot: &mut Option<Field<T>>, line: usize, key: &str, value: T) -> Result<(), NotesError> {
+    if let Some(previous) = slot {
+        return Err(NotesError::new(
+            line,
+            format!("{key:?} already given on line {}", previous.line),
+        ));
+    }
+
+    *slot = Some(Field { line, value });
+    Ok(())
+}
+
+impl Notes {
+    fn field(&mut self, line: usize, key: &str, value: &str) -> Result<(), NotesError> {
+        match key {
+            "Starting items" => {
+                let items = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(|v| number(line, v, "a worry level").map(Item))
+                    .collect::<Result<_, _>>()?;
+                set(&mut self.items, line, key, items)
+            }
+            "Operation" => {
+                let expr = value
+                    .trim()
+                    .strip_prefix("new")
+                    .and_then(|rest| rest.trim_start().strip_prefix('='))
+                    .ok_or_else(|| NotesError::new(line, "operation must be \"new = ...\""))?;
+                let expr = expr
+                    .parse()
+                    .map_err(|err| NotesError::new(line, format!("bad operation: {err}")))?;
+                set(&mut self.operation, line, key, Operation(expr))
+            }
+            "Test" => {
+                let divisor = value
+                    .trim()
+                    .strip_prefix("divisible by")
+                    .ok_or_else(|| NotesError::new(line, "test must be \"divisible by N\""))?;
+                let divisor = number(line, divisor, "a divisor")?;
+                if divisor == 0 {
+                    return Err(NotesError::new(line, "cannot test divisibility by zero"));
+                }
+                set(&mut self.divisor, line, key, divisor)
+            }
+            "If true" | "If false" => {
+                let target = value
+                    .trim()
+                    .strip_prefix("throw to monkey")
+                    .ok_or_else(|| NotesError::new(line, "expected \"throw to monkey N\""))?;
+                let target = number(line, target, "a monkey number")?;
+                if key == "If true" {
+                    set(&mut self.true_target, line, key, target)
+                } else {
+                    set(&mut self.false_target, line, key, target)
+                }
+            }
+            _ => Err(NotesError::new(line, format!("unknown field {key:?}"))),
+        }
+    }
+
+    fn finish(self, line: usize, id: usize) -> Result<(Monkey, [Field<usize>; 2]), NotesError> {
+        fn require<T>(
+            field: Option<Field<T>>,
+            line: usize,
+            id: usize,
+            key: &str,
+        ) -> Result<Field<T>, NotesError> {
+            field.ok_or_else(|| NotesError::new(line, format!("monkey {id} has no {key:?}")))
+        }
+
+        let items = require(self.items, line, id, "Starting items")?;
+        let operation = require(self.operation, line, id, "Operation")?;
+        let divisor = require(self.divisor, line, id, "Test")?;
+        let true_target = require(self.true_target, line, id, "If true")?;
+        let false_target = require(self.false_target, line, id, "If false")?;
+
+        let monkey = Monkey {
+            items: items.value,
+            operation: operation.value,
+            target: TargetTest {
+                divisor: divisor.value,
+                true_target: true_target.value,
+                false_target: false_target.value,
+            },
+            inspections: 0,
+        };
+
+        Ok((monkey, [true_target, false_target]))
+    }
+}
+
+// Reads the monkeys' notes. Each monkey starts with a "Monkey N:" header and
+// its fields may follow in any order with any indentation. Monkeys must be
+// numbered from zero upwards and may only throw to other monkeys that exist.
+pub(super) fn parse(input: &str) -> Result<Vec<Monkey>, NotesError> {
+    let mut monkeys = Vec::new();
+    let mut targets = Vec::new();
+    let mut current: Option<(usize, Notes)> = None;
+
+    for (index, text) in input.lines().enumerate() {
+        let line = index + 1;
+        let text = text.trim();
+
+        if text.is_empty() {
+            continue;
+        }
+
+        if let Some(id) = text
+            .strip_prefix("Monkey ")
+            .and_then(|t| t.strip_suffix(':'))
+        {
+            let id: usize = number(line, id, "a monkey number")?;
+            let expected = monkeys.len() + current.is_some() as usize;
+            if id != expected {
+                return Err(NotesError::new(
+                    line,
+                    format!("expected monkey {expected}, found monkey {id}"),
+                ));
+            }
+
+            if let Some((header, notes)) = current.take() {
+                let (monkey, throws) = notes.finish(header, monkeys.len())?;
+                monkeys.push(monkey);
+                targets.push(throws);
+            }
+
+            current = Some((line, Notes::default()));
+            continue;
+        }
+
+        let Some((_, notes)) = current.as_mut() else {
+            return Err(NotesError::new(line, "expected a \"Monkey N:\" header"));
+        };
+
+        let (key, value) = text.split_once(':').ok_or_else(|| {
+            NotesError::new(line, format!("expected \"field: value\", found {text:?}"))
+        })?;
+        notes.field(line, key.trim(), value)?;
+    }
+
+    if let Some((header, notes)) = current {
+        let (monkey, throws) = notes.finish(header, monkeys.len())?;
+        monkeys.push(monkey);
+        targets.push(throws);
+    }
+
+    if monkeys.is_empty() {
+        return Err(NotesError::new(1, "no monkeys found"));
+    }
+
+    for (id, throws) in targets.iter().enumerate() {
+        for target in throws {
+            if target.value >= monkeys.len() {
+                return Err(NotesError::new(
+                    target.line,
+                    format!(
+                        "monkey {id} throws to monkey {}, which does not exist",
+                        target.value
+                    ),
+                ));
+            }
+
+            if target.value == id {
+                return Err(NotesError::new(
+                    target.line,
+                    format!("monkey {id} throws to itself"),
+                ));
+            }
+        }
+    }
+
+    Ok(monkeys)
+}
@@ -1,67 +1,28 @@
 use itertools::Itertools;
 
-#[derive(Debug)]
-struct Item(usize);
+mod expr;
+mod notes;
+mod simulation;
+mod trajectory;
+mod worry;
 
-#[derive(Debug)]
-enum Operand {
-    Old,
-    Number(usize),
-}
-
-impl From<&str> for Operand {
-    fn from(s: &str) -> Self {
-        if s == "old" {
-            Self::Old
-        } else {
-            Self::Number(s.parse().unwrap())
-        }
-    }
-}
+use expr::Expr;
+use simulation::{Relief, Simulation};
+use trajectory::Trajectory;
+use worry::Worry;
 
 #[derive(Debug)]
-enum Operator {
-    Add,
-    Multiply,
-}
-
-impl From<&str> for Operator {
-    fn from(s: &str) -> Self {
-        match s {
-            "+" => Operator::Add,
-            "*" => Operator::Multiply,
-            _ => panic!("unknown operator"),
-        }
-    }
-}
+struct Item(usize);
 
 #[derive(Debug)]
-struct Operation {
-    op: Operator,
-    arg: Operand,
-}
-
-impl From<&str> for Operation {
-    fn from(s: &str) -> Self {
-        let (op, arg) = s.split_once(' ').unwrap();
-        let op = Operator::from(op);
-        let arg = Operand::from(arg);
-
-        Self { op, arg }
-    }
-}
+struct Operation(Expr);
 
 impl Operation {
     fn perform(&self, item: &mut Item, modulus: usize) {
-        let arg = match self.arg {
-            Operand::Old => item.0,
-            Operand::Number(n) => n,
+        item.0 = match self.0.eval_mod(item.0, modulus) {
+            Ok(value) => value,
+            Err(err) => panic!("cannot evaluate {}: {err}", self.0),
         };
-
-        item.0 = match self.op {
-            Operator::Add => item.0 + arg,
-            Operator::Multiply => item.0 * arg,
-        } % modulus;
     }
 }
 
@@ -73,8 +34,8 @@ struct TargetTest {
 }
 
 impl TargetTest {
-    fn choose(&self, item: &Item) -> usize {
-        if item.0 % self.divisor == 0 {
+    fn choose<W: Worry>(&self, worry: &W) -> usize {
+        if worry.is_multiple_of(self.divisor) {
             self.true_target
         } else {
             self.false_target
@@ -87,104 +48,103 @@ struct Monkey {
     items: Vec<Item>,
     operation: Operation,
     target: TargetTest,
-
-    inspections: usize,
 }
 
-impl Monkey {
-    fn throws(&mut self, modulus: usize) -> Vec<(Item, usize)> {
-        self.items
-            .drain(0..)
-            .map(|mut item| {
-                self.operation.perform(&mut item, modulus);
-                self.inspections += 1;
-                let target = self.target.choose(&item);
-                (item, target)
-            })
-            .collect()
-    }
-
-    fn catch(&mut self, item: Item) {
-        self.items.push(item)
-    }
-}
+// Follows every item on its own until it starts repeating, then works out
+// the inspection counts for any number of rounds without simulating them.
+fn cycles(monkeys: &[Monkey], modulus: usize, rounds: u64) {
+    let mut inspections = vec![0; monkeys.len()];
 
-fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    let input = std::fs::read_to_string(&args[1]).unwrap();
+    for (holder, monkey) in monkeys.iter().enumerate() {
+        for item in &monkey.items {
+            let trajectory = Trajectory::trace(monkeys, holder, item.0, modulus);
 
-    let mut monkeys = Vec::new();
-    let mut modulus = 1;
+            println!(
+                "Item {} from monkey {holder} repeats every {} rounds after {} rounds, \
+                 visiting monkeys {} ({} inspections per cycle)",
+                item.0,
+                trajectory.period(),
+                trajectory.prefix(),
+                trajectory.cycle().iter().sorted().dedup().join(", "),
+                trajectory.cycle().len()
+            );
 
-    for monkey in input.split_terminator("\n\n") {
-        let lines: Vec<&str> = monkey.split_terminator('\n').collect();
+            for (total, count) in inspections
+                .iter_mut()
+                .zip(trajectory.inspections(monkeys.len(), rounds))
+            {
+                *total += count;
+            }
+        }
+    }
 
-        let (_, items) = lines[1].rsplit_once(": ").unwrap();
-        let items: Vec<Item> = items
-            .split(", ")
-            .map(|v| Item(v.parse().unwrap()))
-            .collect();
+    println!();
 
-        let (_, operation) = lines[2].split_once(" = old ").unwrap();
-        let operation = Operation::from(operation);
+    for (i, count) in inspections.iter().enumerate() {
+        println!("Monkey {i} inspected items {count} times after {rounds} rounds.");
+    }
 
-        let (_, divisor) = lines[3].rsplit_once(' ').unwrap();
-        let divisor: usize = divisor.parse().unwrap();
-        modulus *= divisor;
+    inspections.sort_unstable();
+    inspections.reverse();
 
-        let (_, true_target) = lines[4].rsplit_once(' ').unwrap();
-        let true_target: usize = true_target.parse().unwrap();
+    println!();
 
-        let (_, false_target) = lines[5].rsplit_once(' ').unwrap();
-        let false_target: usize = false_target.parse().unwrap();
+    println!(
+        "Monkey business: {}",
+        inspections[0] as u128 * inspections[1] as u128
+    );
+}
 
-        let target = TargetTest {
-            divisor,
-            true_target,
-            false_target,
-        };
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let input = std::fs::read_to_string(&args[1]).unwrap();
 
-        monkeys.push(Monkey {
-            items,
-            operation,
-            target,
-            inspections: 0,
-        });
-    }
+    let monkeys = match notes::parse(&input) {
+        Ok(monkeys) => monkeys,
+        Err(err) => panic!("cannot read notes: {err}"),
+    };
+    let modulus = monkeys.iter().map(|m| m.target.divisor).product();
+
+    let simulation = match args.get(2).map(String::as_str) {
+        Some("cycles") => {
+            for monkey in &monkeys {
+                if let Err(err) = monkey.operation.0.check_modular() {
+                    panic!("cannot follow items through {}: {err}", monkey.operation.0);
+                }
+            }
 
-    for round in 1..=10000 {
-        for monkey in 0..monkeys.len() {
-            for (item, target) in monkeys[monkey].throws(modulus) {
-                monkeys[target].catch(item);
+            let rounds = args.get(3).map_or(10000, |r| r.parse().unwrap());
+            cycles(&monkeys, modulus, rounds);
+            return;
+        }
+        Some("part1") => Simulation::new().relief(Relief::Divide(3)).rounds(20),
+        Some("part2") | None => Simulation::new(),
+        Some(relief) => {
+            let simulation = Simulation::new().relief(relief.parse().unwrap());
+            match args.get(3) {
+                Some(rounds) => simulation.rounds(rounds.parse().unwrap()),
+                None => simulation,
             }
         }
+    };
 
-        println!("After round {round}, the monkeys are holding items with these worry levels:");
-        for monkey in 0..monkeys.len() {
-            println!(
-                "Monkey {monkey}: {}",
-                monkeys[monkey]
-                    .items
-                    .iter()
-                    .map(|i| format!("{}", i.0))
-                    .join(", ")
-            );
-        }
-    }
+    let simulation = match args.get(4) {
+        Some(top) => simulation.top(top.parse().unwrap()),
+        None => simulation,
+    };
+
+    let outcome = match simulation.trace(true).run(&monkeys) {
+        Ok(outcome) => outcome,
+        Err(err) => panic!("simulation failed: {err}"),
+    };
 
     println!();
 
-    for (i, monkey) in monkeys.iter().enumerate() {
-        println!("Monkey {i} inspected items {} times.", monkey.inspections);
+    for (i, count) in outcome.inspections.iter().enumerate() {
+        println!("Monkey {i} inspected items {count} times.");
     }
 
-    monkeys.sort_unstable_by_key(|m| m.inspections);
-    monkeys.reverse();
-
     println!();
 
-    println!(
-        "Monkey business: {}",
-        monkeys[0].inspections * monkeys[1].inspections
-    );
+    println!("Monkey business: {}", outcome.business);
 }
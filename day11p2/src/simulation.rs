@@ -0,0 +1,145 @@
+use std::str::FromStr;
+
+use itertools::Itertools;
+use num::BigUint;
+
+use crate::expr::{Expr, ExprError};
+use crate::worry::Worry;
+use crate::Monkey;
+
+// What happens to an item's worry level once a monkey has inspected it.
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Relief {
+    // Divide by a constant, rounding down.
+    Divide(usize),
+    // Keep only the remainder modulo the product of the monkeys' divisors.
+    Modulus,
+    // Nothing; worry levels grow without bound.
+    None,
+}
+
+impl FromStr for Relief {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "modulus" => Ok(Self::Modulus),
+            "none" => Ok(Self::None),
+            _ => match s.strip_prefix("divide=").map(str::parse) {
+                Some(Ok(0)) => Err("cannot divide worry levels by zero".to_owned()),
+                Some(Ok(k)) => Ok(Self::Divide(k)),
+                _ => Err(format!("unknown relief {s:?}")),
+            },
+        }
+    }
+}
+
+pub(super) struct Outcome {
+    pub(super) inspections: Vec<usize>,
+    pub(super) business: u128,
+}
+
+pub(super) struct Simulation {
+    relief: Relief,
+    rounds: usize,
+    top: usize,
+    trace: bool,
+}
+
+impl Simulation {
+    pub(super) fn new() -> Self {
+        Self {
+            relief: Relief::Modulus,
+            rounds: 10000,
+            top: 2,
+            trace: false,
+        }
+    }
+
+    pub(super) fn relief(mut self, relief: Relief) -> Self {
+        self.relief = relief;
+        self
+    }
+
+    pub(super) fn rounds(mut self, rounds: usize) -> Self {
+        self.rounds = rounds;
+        self
+    }
+
+    // How many of the busiest monkeys to multiply together for the monkey
+    // business.
+    pub(super) fn top(mut self, top: usize) -> Self {
+        self.top = top;
+        self
+    }
+
+    // Prints the items every monkey holds after each round.
+    pub(super) fn trace(mut self, trace: bool) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    pub(super) fn run(&self, monkeys: &[Monkey]) -> Result<Outcome, ExprError> {
+        match self.relief {
+            Relief::Divide(k) => {
+                self.simulate(monkeys, |expr: &Expr, old: &usize| Ok(expr.eval(old)? / k))
+            }
+            Relief::Modulus => {
+                for monkey in monkeys {
+                    monkey.operation.0.check_modular()?;
+                }
+
+                let modulus = monkeys.iter().map(|m| m.target.divisor).product();
+                self.simulate(monkeys, |expr: &Expr, old: &usize| {
+                    expr.eval_mod(*old, modulus)
+                })
+            }
+            Relief::None => self.simulate(monkeys, |expr: &Expr, old: &BigUint| expr.eval(old)),
+        }
+    }
+
+    fn simulate<W, F>(&self, monkeys: &[Monkey], inspect: F) -> Result<Outcome, ExprError>
+    where
+        W: Worry,
+        F: Fn(&Expr, &W) -> Result<W, ExprError>,
+    {
+        let mut holding: Vec<Vec<W>> = monkeys
+            .iter()
+            .map(|m| m.items.iter().map(|item| W::from(item.0)).collect())
+            .collect();
+        let mut inspections = vec![0; monkeys.len()];
+
+        for round in 1..=self.rounds {
+            for (i, monkey) in monkeys.iter().enumerate() {
+                for old in std::mem::take(&mut holding[i]) {
+                    let worry = inspect(&monkey.operation.0, &old)?;
+                    inspections[i] += 1;
+
+                    let target = monkey.target.choose(&worry);
+                    holding[target].push(worry);
+                }
+            }
+
+            if self.trace {
+                println!(
+                    "After round {round}, the monkeys are holding items with these worry levels:"
+                );
+                for (i, items) in holding.iter().enumerate() {
+                    println!("Monkey {i}: {}", items.iter().join(", "));
+                }
+            }
+        }
+
+        let business = inspections
+            .iter()
+            .sorted_unstable_by(|a, b| b.cmp(a))
+            .take(self.top)
+            .map(|count| *count as u128)
+            .product();
+
+        Ok(Outcome {
+            inspections,
+            business,
+        })
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use crate::{Item, Monkey};
+
+// The path of a single item through the monkeys. An item's journey depends
+// only on which monkey holds it and its worry level modulo the product of
+// the divisors, so once that state repeats at the start of a round the item
+// is in a cycle and every later round can be read off the recorded path.
+pub(super) struct Trajectory {
+    // Monkeys that inspected the item, in order.
+    path: Vec<usize>,
+    // Where each round starts in `path`; one more entry than rounds traced.
+    rounds: Vec<usize>,
+    // Rounds before the cycle is entered and rounds per trip round it.
+    prefix: usize,
+    period: usize,
+}
+
+impl Trajectory {
+    pub(super) fn trace(monkeys: &[Monkey], holder: usize, worry: usize, modulus: usize) -> Self {
+        let mut item = Item(worry % modulus);
+        let mut holder = holder;
+
+        let mut path = Vec::new();
+        let mut rounds = vec![0];
+        let mut seen = HashMap::new();
+
+        loop {
+            if let Some(first) = seen.insert((holder, item.0), rounds.len() - 1) {
+                return Self {
+                    path,
+                    prefix: first,
+                    period: rounds.len() - 1 - first,
+                    rounds,
+                };
+            }
+
+            // Monkeys take turns in order, so an item thrown to a later
+            // monkey is inspected again in the same round.
+            loop {
+                let monkey = &monkeys[holder];
+                monkey.operation.perform(&mut item, modulus);
+                path.push(holder);
+
+                let target = monkey.target.choose(&item.0);
+                let next_round = target <= holder;
+                holder = target;
+
+                if next_round {
+                    break;
+                }
+            }
+
+            rounds.push(path.len());
+        }
+    }
+
+    pub(super) fn prefix(&self) -> usize {
+        self.prefix
+    }
+
+    pub(super) fn period(&self) -> usize {
+        self.period
+    }
+
+    // The monkeys that inspect the item during one trip round its cycle.
+    pub(super) fn cycle(&self) -> &[usize] {
+        &self.path[self.rounds[self.prefix]..self.rounds[self.prefix + self.period]]
+    }
+
+    fn count(counts: &mut [u64], path: &[usize], times: u64) {
+        for monkey in path {
+            counts[*monkey] += times;
+        }
+    }
+
+    // How many times each monkey inspects this item over the given number
+    // of rounds.
+    pub(super) fn inspections(&self, monkeys: usize, rounds: u64) -> Vec<u64> {
+        let mut counts = vec![0; monkeys];
+
+        let prefix = rounds.min(self.prefix as u64) as usize;
+        Self::count(&mut counts, &self.path[..self.rounds[prefix]], 1);
+
+        let remaining = rounds - prefix as u64;
+        let laps = remaining / self.period as u64;
+        let partial = (remaining % self.period as u64) as usize;
+
+        Self::count(&mut counts, self.cycle(), laps);
+
+        let start = self.rounds[self.prefix];
+        let end = self.rounds[self.prefix + partial];
+        Self::count(&mut counts, &self.path[start..end], 1);
+
+        counts
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+use num::{BigUint, Zero};
+
+// A worry level. Plain integers are enough while something keeps the levels
+// in check; with no relief at all they outgrow any fixed width.
+pub(super) trait Worry: Clone + From<usize> + fmt::Display {
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+    fn checked_sub(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+    fn checked_div(&self, rhs: &Self) -> Option<Self>;
+    fn checked_rem(&self, rhs: &Self) -> Option<Self>;
+    fn is_multiple_of(&self, divisor: usize) -> bool;
+}
+
+impl Worry for usize {
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        usize::checked_add(*self, *rhs)
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        usize::checked_sub(*self, *rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        usize::checked_mul(*self, *rhs)
+    }
+
+    fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        usize::checked_div(*self, *rhs)
+    }
+
+    fn checked_rem(&self, rhs: &Self) -> Option<Self> {
+        usize::checked_rem(*self, *rhs)
+    }
+
+    fn is_multiple_of(&self, divisor: usize) -> bool {
+        usize::is_multiple_of(*self, divisor)
+    }
+}
+
+impl Worry for BigUint {
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        (self >= rhs).then(|| self - rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+
+    fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        (!rhs.is_zero()).then(|| self / rhs)
+    }
+
+    fn checked_rem(&self, rhs: &Self) -> Option<Self> {
+        (!rhs.is_zero()).then(|| self % rhs)
+    }
+
+    fn is_multiple_of(&self, divisor: usize) -> bool {
+        (self % divisor).is_zero()
+    }
+}
@@ -28,21 +28,19 @@ pub trait AStar {
             let score = scores
                 .get(&current)
                 .copied()
-                .unwrap_or_else(|| Self::Weight::max_value());
+                .unwrap_or_else(Self::Weight::max_value);
 
             for neighbour in self.neighbours(current) {
                 let tentative_score = score + self.weight(current, neighbour);
                 let neighbour_score = scores
                     .get(&neighbour)
                     .copied()
-                    .unwrap_or_else(|| Self::Weight::max_value());
+                    .unwrap_or_else(Self::Weight::max_value);
                 if tentative_score < neighbour_score {
                     came_from.insert(neighbour, current);
                     scores.insert(neighbour, tentative_score);
-                    open_set.push_decrease(
-                        neighbour,
-                        tentative_score + self.heuristic(current, neighbour),
-                    );
+                    open_set
+                        .push_decrease(neighbour, tentative_score + self.heuristic(neighbour, to));
                 }
             }
         }
@@ -1,8 +1,14 @@
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
 
 mod astar;
+mod render;
+mod rules;
 
 use astar::AStar;
+use render::{Overlay, Style};
+use rules::Rules;
 
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 struct Point {
@@ -23,6 +29,10 @@ impl Point {
     fn manhattan_distance(&self, other: &Point) -> usize {
         self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
     }
+
+    fn chebyshev_distance(&self, other: &Point) -> usize {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
 }
 
 impl From<(usize, usize)> for Point {
@@ -41,6 +51,8 @@ struct Map {
     end: Point,
 
     max: Point,
+
+    rules: Rules,
 }
 
 impl FromIterator<char> for Map {
@@ -68,7 +80,7 @@ impl FromIterator<char> for Map {
                     end = Some(point);
                     point.advance_x();
                 }
-                ch if ch >= 'a' && ch <= 'z' => {
+                ch if ch.is_ascii_lowercase() => {
                     elevation.insert(point, ch as u32 - 97);
                     point.advance_x();
                 }
@@ -91,45 +103,61 @@ impl FromIterator<char> for Map {
             start,
             end,
             max: Point::from((max_x, max_y)),
+            rules: Rules::default(),
         }
     }
 }
 
+impl Map {
+    fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+}
+
 impl AStar for Map {
     type Node = Point;
     type Weight = usize;
 
+    // Every step costs at least the flat step cost, so counting the fewest
+    // moves never overestimates.
     fn heuristic(&self, from: Point, to: Point) -> usize {
-        from.manhattan_distance(&to)
+        let moves = if self.rules.diagonal {
+            from.chebyshev_distance(&to)
+        } else {
+            from.manhattan_distance(&to)
+        };
+        moves * self.rules.cost.step
     }
 
-    fn weight(&self, _from: Point, _to: Point) -> usize {
-        1
+    fn weight(&self, from: Point, to: Point) -> usize {
+        self.rules
+            .cost
+            .of(self.elevation[&from], self.elevation[&to])
     }
 
     fn neighbours(&self, node: Point) -> Vec<Point> {
-        let mut points = Vec::new();
-
-        if node.x > 0 {
-            points.push(Point::from((node.x - 1, node.y)));
-        }
-        if node.x + 1 < self.max.x {
-            points.push(Point::from((node.x + 1, node.y)));
-        }
-        if node.y > 0 {
-            points.push(Point::from((node.x, node.y - 1)));
-        }
-        if node.y + 1 < self.max.y {
-            points.push(Point::from((node.x, node.y + 1)));
-        }
-
-        let elevation = *self.elevation.get(&node).unwrap();
-        points.retain(|p| {
-            let neighbour_elevation = *self.elevation.get(p).unwrap();
-            neighbour_elevation < elevation || neighbour_elevation - elevation <= 1
-        });
-
-        points
+        const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+        const DIAGONAL: [(isize, isize); 4] = [(-1, -1), (1, -1), (-1, 1), (1, 1)];
+
+        let moves = if self.rules.diagonal {
+            &[ORTHOGONAL, DIAGONAL][..]
+        } else {
+            &[ORTHOGONAL][..]
+        };
+
+        let elevation = self.elevation[&node];
+
+        moves
+            .iter()
+            .flatten()
+            .filter_map(|(dx, dy)| {
+                let x = node.x.checked_add_signed(*dx)?;
+                let y = node.y.checked_add_signed(*dy)?;
+                (x < self.max.x && y < self.max.y).then(|| Point::from((x, y)))
+            })
+            .filter(|p| self.rules.allows(elevation, self.elevation[p]))
+            .collect()
     }
 }
 
@@ -152,7 +180,7 @@ impl std::fmt::Display for Map {
                 }
             }
 
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
 
         Ok(())
@@ -163,10 +191,38 @@ fn main() {
     let args: Vec<String> = std::env::args().collect();
     let input = std::fs::read_to_string(&args[1]).unwrap();
 
-    let map = Map::from_iter(input.chars());
+    // Options like "climb=2" or "diagonal" change the rules; anything else
+    // picks how to draw the route.
+    let (options, rest): (Vec<&String>, Vec<&String>) = args[2..]
+        .iter()
+        .partition(|a| a.contains('=') || a.as_str() == "diagonal");
+
+    let mut rules = Rules::default();
+    for option in options {
+        rules.set(option).unwrap();
+    }
+
+    let map = Map::from_iter(input.chars()).with_rules(rules);
     println!("{map}");
-    println!(
-        "{} steps",
-        map.find_path(map.start, map.end).unwrap().len() - 1
-    );
+
+    let Some(path) = map.find_path(map.start, map.end) else {
+        println!("cannot reach the end under these rules");
+        return;
+    };
+    let cost: usize = path.windows(2).map(|s| map.weight(s[0], s[1])).sum();
+    println!("{} steps, costing {cost}", path.len() - 1);
+
+    match rest.first().map(|s| s.as_str()) {
+        Some("plain") => print!("\n{}", Overlay::new(&map, &path, Style::Plain)),
+        Some("dim") => print!("\n{}", Overlay::new(&map, &path, Style::Dim)),
+        Some("colour") => print!("\n{}", Overlay::new(&map, &path, Style::Colour)),
+        Some("ppm") => {
+            let pixel_size = rest.get(2).map_or(8, |s| s.parse().unwrap());
+            let out = BufWriter::new(File::create(rest[1]).unwrap());
+            Overlay::new(&map, &path, Style::Colour)
+                .write_ppm(out, pixel_size)
+                .unwrap();
+        }
+        _ => (),
+    }
 }
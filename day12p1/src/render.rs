@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::{Map, Point};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Style {
+    // As drawn in the puzzle text: arrows along the route, '.' elsewhere.
+    Plain,
+    // Elevation letters off the route, dimmed.
+    Dim,
+    // As Dim, with every cell shaded by its elevation.
+    Colour,
+}
+
+// A route drawn over the heightmap.
+pub(super) struct Overlay<'a> {
+    map: &'a Map,
+    arrows: HashMap<Point, char>,
+    style: Style,
+}
+
+fn arrow(from: Point, to: Point) -> char {
+    use std::cmp::Ordering::*;
+
+    match (to.x.cmp(&from.x), to.y.cmp(&from.y)) {
+        (Greater, Equal) => '>',
+        (Less, Equal) => '<',
+        (Equal, Greater) => 'v',
+        (Equal, Less) => '^',
+        (Greater, Less) => '↗',
+        (Greater, Greater) => '↘',
+        (Less, Greater) => '↙',
+        (Less, Less) => '↖',
+        (Equal, Equal) => '·',
+    }
+}
+
+// Green valleys through brown slopes to snowy peaks.
+fn shade(elevation: u32) -> [u8; 3] {
+    const STOPS: [[f64; 3]; 3] = [
+        [30.0, 110.0, 40.0],
+        [140.0, 100.0, 50.0],
+        [240.0, 240.0, 240.0],
+    ];
+
+    let t = elevation.min(25) as f64 / 25.0 * 2.0;
+    let (lower, upper, t) = if t < 1.0 {
+        (STOPS[0], STOPS[1], t)
+    } else {
+        (STOPS[1], STOPS[2], t - 1.0)
+    };
+
+    [0, 1, 2].map(|i| (lower[i] + (upper[i] - lower[i]) * t) as u8)
+}
+
+impl<'a> Overlay<'a> {
+    pub(super) fn new(map: &'a Map, path: &[Point], style: Style) -> Self {
+        let arrows = path
+            .windows(2)
+            .map(|step| (step[0], arrow(step[0], step[1])))
+            .collect();
+
+        Self { map, arrows, style }
+    }
+
+    fn letter(&self, point: Point) -> char {
+        if point == self.map.start {
+            'S'
+        } else if point == self.map.end {
+            'E'
+        } else {
+            char::from_u32(self.map.elevation[&point] + 'a' as u32).unwrap()
+        }
+    }
+
+    // Writes a binary PPM with `pixel_size` pixels per cell. Cells on the
+    // route are drawn red; the rest are shaded by elevation, darkened.
+    pub(super) fn write_ppm<W: Write>(&self, mut out: W, pixel_size: usize) -> io::Result<()> {
+        let (width, height) = (self.map.max.x, self.map.max.y);
+        write!(
+            out,
+            "P6\n{} {}\n255\n",
+            width * pixel_size,
+            height * pixel_size
+        )?;
+
+        for y in 0..height {
+            let line: Vec<u8> = (0..width)
+                .flat_map(|x| {
+                    let point = Point::from((x, y));
+                    let colour = if point == self.map.end {
+                        [255, 215, 0]
+                    } else if self.arrows.contains_key(&point) {
+                        [220, 40, 40]
+                    } else {
+                        shade(self.map.elevation[&point]).map(|c| c / 2)
+                    };
+                    colour.repeat(pixel_size)
+                })
+                .collect();
+
+            for _ in 0..pixel_size {
+                out.write_all(&line)?;
+            }
+        }
+
+        out.flush()
+    }
+}
+
+impl fmt::Display for Overlay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.map.max.y {
+            for x in 0..self.map.max.x {
+                let point = Point::from((x, y));
+                let arrow = self.arrows.get(&point).copied();
+
+                if self.style == Style::Plain {
+                    let c = match arrow {
+                        Some(arrow) => arrow,
+                        None if point == self.map.end => 'E',
+                        None => '.',
+                    };
+                    write!(f, "{c}")?;
+                    continue;
+                }
+
+                if self.style == Style::Colour {
+                    let [r, g, b] = shade(self.map.elevation[&point]);
+                    write!(f, "\x1b[48;2;{r};{g};{b}m")?;
+                }
+
+                match arrow {
+                    Some(arrow) => write!(f, "\x1b[1m{arrow}")?,
+                    None if point == self.map.end => write!(f, "\x1b[1mE")?,
+                    None => write!(f, "\x1b[2m{}", self.letter(point))?,
+                }
+                write!(f, "\x1b[0m")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use std::str::FromStr;
+
+// What it costs to take one step, given how far it climbs or descends.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Cost {
+    pub(super) step: usize,
+    pub(super) per_up: usize,
+    pub(super) per_down: usize,
+}
+
+impl Cost {
+    pub(super) fn of(&self, from: u32, to: u32) -> usize {
+        let up = to.saturating_sub(from) as usize;
+        let down = from.saturating_sub(to) as usize;
+        self.step + up * self.per_up + down * self.per_down
+    }
+}
+
+impl Default for Cost {
+    fn default() -> Self {
+        Self {
+            step: 1,
+            per_up: 0,
+            per_down: 0,
+        }
+    }
+}
+
+impl FromStr for Cost {
+    type Err = String;
+
+    // "step,up,down", e.g. "1,3,0" to make every metre of climbing cost three
+    // extra steps.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let parts: Vec<usize> = s
+            .split(',')
+            .map(|p| p.trim().parse().map_err(|_| format!("bad cost {s:?}")))
+            .collect::<Result<_, _>>()?;
+
+        match parts[..] {
+            [step, per_up, per_down] => Ok(Self {
+                step,
+                per_up,
+                per_down,
+            }),
+            _ => Err(format!("cost needs three numbers, got {s:?}")),
+        }
+    }
+}
+
+// How we are allowed to move around the heightmap. The defaults are the
+// puzzle's: four directions, at most one step up, any drop down.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Rules {
+    pub(super) climb: u32,
+    pub(super) descent: Option<u32>,
+    pub(super) diagonal: bool,
+    pub(super) cost: Cost,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            climb: 1,
+            descent: None,
+            diagonal: false,
+            cost: Cost::default(),
+        }
+    }
+}
+
+impl Rules {
+    pub(super) fn allows(&self, from: u32, to: u32) -> bool {
+        if to >= from {
+            to - from <= self.climb
+        } else {
+            self.descent.is_none_or(|limit| from - to <= limit)
+        }
+    }
+
+    // Applies a "climb=2", "descent=1", "diagonal" or "cost=1,3,0" option.
+    pub(super) fn set(&mut self, option: &str) -> Result<(), String> {
+        let (key, value) = option.split_once('=').unwrap_or((option, ""));
+        let number = || value.parse().map_err(|_| format!("bad {key} {value:?}"));
+
+        match key {
+            "climb" => self.climb = number()?,
+            "descent" => self.descent = Some(number()?),
+            "diagonal" => self.diagonal = true,
+            "cost" => self.cost = value.parse()?,
+            _ => return Err(format!("unknown rule {option:?}")),
+        }
+
+        Ok(())
+    }
+}
@@ -1,8 +1,14 @@
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
 
-mod astar;
+mod regions;
+mod render;
+mod search;
 
-use astar::AStar;
+use regions::Connectivity;
+use render::{Overlay, Style};
+use search::ReverseSearch;
 
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 struct Point {
@@ -19,10 +25,6 @@ impl Point {
         self.x = 0;
         self.y += 1;
     }
-
-    fn manhattan_distance(&self, other: &Point) -> usize {
-        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
-    }
 }
 
 impl From<(usize, usize)> for Point {
@@ -68,7 +70,7 @@ impl FromIterator<char> for Map {
                     end = Some(point);
                     point.advance_x();
                 }
-                ch if ch >= 'a' && ch <= 'z' => {
+                ch if ch.is_ascii_lowercase() => {
                     elevation.insert(point, ch as u32 - 97);
                     point.advance_x();
                 }
@@ -95,41 +97,21 @@ impl FromIterator<char> for Map {
     }
 }
 
-impl AStar for Map {
-    type Node = Point;
-    type Weight = usize;
-
-    fn heuristic(&self, from: Point, to: Point) -> usize {
-        from.manhattan_distance(&to)
+impl Map {
+    fn adjacent(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        [(-1, 0), (1, 0), (0, -1), (0, 1)]
+            .into_iter()
+            .filter_map(move |(dx, dy)| {
+                let x = point.x.checked_add_signed(dx)?;
+                let y = point.y.checked_add_signed(dy)?;
+                (x < self.max.x && y < self.max.y).then(|| Point::from((x, y)))
+            })
     }
 
-    fn weight(&self, _from: Point, _to: Point) -> usize {
-        1
-    }
-
-    fn neighbours(&self, node: Point) -> Vec<Point> {
-        let mut points = Vec::new();
-
-        if node.x > 0 {
-            points.push(Point::from((node.x - 1, node.y)));
-        }
-        if node.x + 1 < self.max.x {
-            points.push(Point::from((node.x + 1, node.y)));
-        }
-        if node.y > 0 {
-            points.push(Point::from((node.x, node.y - 1)));
-        }
-        if node.y + 1 < self.max.y {
-            points.push(Point::from((node.x, node.y + 1)));
-        }
-
-        let elevation = *self.elevation.get(&node).unwrap();
-        points.retain(|p| {
-            let neighbour_elevation = *self.elevation.get(p).unwrap();
-            neighbour_elevation < elevation || neighbour_elevation - elevation <= 1
-        });
-
-        points
+    // Whether one step can go from `from` to the neighbouring `to`: at most
+    // one up, any distance down.
+    fn can_climb(&self, from: Point, to: Point) -> bool {
+        self.elevation[&to] <= self.elevation[&from] + 1
     }
 }
 
@@ -152,44 +134,65 @@ impl std::fmt::Display for Map {
                 }
             }
 
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
 
         Ok(())
     }
 }
 
-struct MapIterator<'a> {
-    point: Point,
-    map: &'a Map,
-}
-
-impl<'a> Iterator for MapIterator<'a> {
-    type Item = (Point, u32);
+fn report(map: &Map, connectivity: Connectivity) {
+    let singletons = connectivity.regions.iter().filter(|r| r.len() == 1).count();
+    println!(
+        "\n{} strongly connected regions, {singletons} of them single cells",
+        connectivity.regions.len()
+    );
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let point = self.point.clone();
-        self.point.advance_x();
-        if self.point.x == self.map.max.x {
-            self.point.advance_y();
-        }
-        if self.point.y == self.map.max.y {
-            None
-        } else {
-            Some((point, *self.map.elevation.get(&point).unwrap()))
-        }
+    for region in connectivity.regions.iter().filter(|r| r.len() > 1) {
+        let low = region.iter().map(|p| map.elevation[p]).min().unwrap();
+        let high = region.iter().map(|p| map.elevation[p]).max().unwrap();
+        let contains_end = region.contains(&map.end);
+        println!(
+            "  {} cells, elevations {}..={}, e.g. {:?}{}",
+            region.len(),
+            char::from_u32(low + 'a' as u32).unwrap(),
+            char::from_u32(high + 'a' as u32).unwrap(),
+            region[0],
+            if contains_end {
+                ", contains the end"
+            } else {
+                ""
+            }
+        );
     }
-}
 
-impl<'a> IntoIterator for &'a Map {
-    type Item = (Point, u32);
-    type IntoIter = MapIterator<'a>;
+    println!(
+        "\n{} cells cannot reach the end (marked #):",
+        connectivity.stranded.len()
+    );
+    for y in 0..map.max.y {
+        let line: String = (0..map.max.x)
+            .map(|x| {
+                let point = Point::from((x, y));
+                if connectivity.reaching.contains(&point) {
+                    char::from_u32(map.elevation[&point] + 'a' as u32).unwrap()
+                } else {
+                    '#'
+                }
+            })
+            .collect();
+        println!("{line}");
+    }
 
-    fn into_iter(self) -> Self::IntoIter {
-        MapIterator {
-            point: Point::default(),
-            map: self,
-        }
+    println!(
+        "\n{} cliff edges hold them back:",
+        connectivity.cliffs.len()
+    );
+    for cliff in &connectivity.cliffs {
+        println!(
+            "  {:?} -> {:?} climbs {}",
+            cliff.below, cliff.above, cliff.height
+        );
     }
 }
 
@@ -199,23 +202,60 @@ fn main() {
 
     let map = Map::from_iter(input.chars());
     println!("{map}");
-    println!(
-        "{} steps",
-        map.find_path(map.start, map.end).unwrap().len() - 1
-    );
 
-    let mut distances = Vec::new();
+    let mut search = ReverseSearch::new(&map, map.end);
 
-    for point in (&map)
-        .into_iter()
-        .filter_map(|(p, e)| if e == 0 { Some(p) } else { None })
-    {
-        if let Some(path) = map.find_path(point, map.end) {
-            distances.push((point, path.len() - 1));
+    match search.distance(map.start) {
+        Some(steps) => println!("{steps} steps"),
+        None => println!("cannot reach the end from the start"),
+    }
+
+    if let Some((start, distance)) = search.nearest(|_, elevation| elevation == 0) {
+        println!("{:?} -> {} steps", start, distance);
+    }
+
+    match args.get(2).map(String::as_str) {
+        Some(mode @ ("plain" | "dim" | "colour")) => {
+            let style = match mode {
+                "plain" => Style::Plain,
+                "dim" => Style::Dim,
+                _ => Style::Colour,
+            };
+            let path = search.path(map.start).unwrap_or_default();
+            println!();
+            print!("{}", Overlay::new(&map, &path, style));
+        }
+        Some("ppm") => {
+            let path = search.path(map.start).unwrap_or_default();
+            let pixel_size = args.get(4).map_or(8, |s| s.parse().unwrap());
+            let out = BufWriter::new(File::create(&args[3]).unwrap());
+            Overlay::new(&map, &path, Style::Colour)
+                .write_ppm(out, pixel_size)
+                .unwrap();
         }
+        Some("regions") => (),
+        // Any other elevation, given as a letter.
+        Some(arg) if arg.len() == 1 && arg.as_bytes()[0].is_ascii_lowercase() => {
+            let letter = arg.as_bytes()[0] as char;
+            let wanted = letter as u32 - 'a' as u32;
+            match search.nearest(|_, elevation| elevation == wanted) {
+                Some((point, distance)) => {
+                    println!("nearest {letter}: {point:?} -> {distance} steps")
+                }
+                None => println!("no {letter} can reach the end"),
+            }
+        }
+        Some(mode) => panic!("unknown mode {mode:?}"),
+        None => (),
     }
 
-    distances.sort_unstable_by_key(|(_, d)| *d);
-    let (start, distance) = distances[0];
-    println!("{:?} -> {} steps", start, distance);
+    let distances = search.distances();
+    println!("{} cells can reach the end", distances.len());
+
+    if args.get(2).map(String::as_str) == Some("regions") {
+        report(
+            &map,
+            regions::analyse(&map, distances.into_keys().collect()),
+        );
+    }
 }
@@ -0,0 +1,116 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Map, Point};
+
+// A step between neighbours that the climbing rule forbids, from a cell that
+// cannot reach the end up to one that can.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Cliff {
+    pub(super) below: Point,
+    pub(super) above: Point,
+    pub(super) height: u32,
+}
+
+pub(super) struct Connectivity {
+    // Strongly connected regions: every cell in one can reach every other.
+    // Largest first.
+    pub(super) regions: Vec<Vec<Point>>,
+    pub(super) reaching: HashSet<Point>,
+    pub(super) stranded: Vec<Point>,
+    pub(super) cliffs: Vec<Cliff>,
+}
+
+fn cells(map: &Map) -> impl Iterator<Item = Point> + '_ {
+    (0..map.max.y).flat_map(move |y| (0..map.max.x).map(move |x| Point::from((x, y))))
+}
+
+// Depth-first search from every unvisited cell, recording cells as they
+// finish. Done with an explicit stack; real inputs are deep enough to make
+// recursion uncomfortable.
+fn finish_order(map: &Map) -> Vec<Point> {
+    let mut visited = HashSet::new();
+    let mut order = Vec::new();
+
+    for root in cells(map) {
+        if !visited.insert(root) {
+            continue;
+        }
+
+        let mut stack = vec![(root, map.adjacent(root).collect::<Vec<_>>())];
+
+        while let Some((here, pending)) = stack.last_mut() {
+            let here = *here;
+
+            match pending.pop() {
+                Some(next) if map.can_climb(here, next) && visited.insert(next) => {
+                    stack.push((next, map.adjacent(next).collect()));
+                }
+                Some(_) => (),
+                None => {
+                    order.push(here);
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    order
+}
+
+// Kosaraju's algorithm: walking the reversed climbing rule in reverse finish
+// order picks out one strongly connected region at a time.
+fn strongly_connected(map: &Map) -> Vec<Vec<Point>> {
+    let mut region_of: HashMap<Point, usize> = HashMap::new();
+    let mut regions = Vec::new();
+
+    for root in finish_order(map).into_iter().rev() {
+        if region_of.contains_key(&root) {
+            continue;
+        }
+
+        let index = regions.len();
+        let mut region = Vec::new();
+        let mut stack = vec![root];
+        region_of.insert(root, index);
+
+        while let Some(here) = stack.pop() {
+            region.push(here);
+
+            for prev in map.adjacent(here) {
+                if map.can_climb(prev, here) && !region_of.contains_key(&prev) {
+                    region_of.insert(prev, index);
+                    stack.push(prev);
+                }
+            }
+        }
+
+        regions.push(region);
+    }
+
+    regions.sort_by_key(|region| std::cmp::Reverse(region.len()));
+    regions
+}
+
+pub(super) fn analyse(map: &Map, reaching: HashSet<Point>) -> Connectivity {
+    let stranded: Vec<Point> = cells(map).filter(|p| !reaching.contains(p)).collect();
+
+    let cliffs = stranded
+        .iter()
+        .flat_map(|below| {
+            map.adjacent(*below)
+                .filter(|above| reaching.contains(above))
+                .map(move |above| Cliff {
+                    below: *below,
+                    above,
+                    height: map.elevation[&above] - map.elevation[below],
+                })
+        })
+        .collect();
+
+    Connectivity {
+        regions: strongly_connected(map),
+        reaching,
+        stranded,
+        cliffs,
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{Map, Point};
+
+// A breadth-first search outwards from the goal, following the climbing rule
+// backwards: we may step from `here` to a neighbour only if the neighbour
+// could have climbed to `here`. The distance to any cell is then the length
+// of the shortest climb from that cell to the goal.
+//
+// The search only expands as far as it needs to, and remembers everything it
+// has settled, so any number of queries can share one traversal.
+pub(super) struct ReverseSearch<'a> {
+    map: &'a Map,
+    distances: HashMap<Point, usize>,
+    queue: VecDeque<Point>,
+    // Cells in the order they were settled, so in order of distance.
+    settled: Vec<Point>,
+}
+
+impl<'a> ReverseSearch<'a> {
+    pub(super) fn new(map: &'a Map, goal: Point) -> Self {
+        Self {
+            map,
+            distances: HashMap::from([(goal, 0)]),
+            queue: VecDeque::from([goal]),
+            settled: Vec::new(),
+        }
+    }
+
+    fn predecessors(&self, here: Point) -> impl Iterator<Item = Point> + 'a {
+        let map = self.map;
+        map.adjacent(here).filter(move |p| map.can_climb(*p, here))
+    }
+
+    // Settles the next cell, returning it, or None once everything that can
+    // reach the goal has been found.
+    fn expand(&mut self) -> Option<Point> {
+        let here = self.queue.pop_front()?;
+        let distance = self.distances[&here];
+
+        let next: Vec<Point> = self
+            .predecessors(here)
+            .filter(|p| !self.distances.contains_key(p))
+            .collect();
+
+        for point in next {
+            self.distances.insert(point, distance + 1);
+            self.queue.push_back(point);
+        }
+
+        self.settled.push(here);
+        Some(here)
+    }
+
+    // The nearest cell matching `target`, with its distance from the goal.
+    pub(super) fn nearest<F>(&mut self, target: F) -> Option<(Point, usize)>
+    where
+        F: Fn(Point, u32) -> bool,
+    {
+        let map = self.map;
+        let matches = |p: &Point| target(*p, map.elevation[p]);
+
+        let found = match self.settled.iter().find(|p| matches(p)) {
+            Some(point) => Some(*point),
+            None => std::iter::from_fn(|| self.expand()).find(|p| matches(p)),
+        };
+
+        found.map(|point| (point, self.distances[&point]))
+    }
+
+    pub(super) fn distance(&mut self, point: Point) -> Option<usize> {
+        self.nearest(|p, _| p == point)
+            .map(|(_, distance)| distance)
+    }
+
+    // A shortest climb from `from` to the goal, found by repeatedly stepping
+    // to a reachable neighbour one step closer.
+    pub(super) fn path(&mut self, from: Point) -> Option<Vec<Point>> {
+        let mut distance = self.distance(from)?;
+        let mut path = vec![from];
+        let mut here = from;
+
+        while distance > 0 {
+            distance -= 1;
+            here = self
+                .map
+                .adjacent(here)
+                .find(|p| self.map.can_climb(here, *p) && self.distances.get(p) == Some(&distance))
+                .unwrap();
+            path.push(here);
+        }
+
+        Some(path)
+    }
+
+    // Runs the search to completion and returns the distance to every cell
+    // that can reach the goal.
+    pub(super) fn distances(mut self) -> HashMap<Point, usize> {
+        while self.expand().is_some() {}
+        self.distances
+    }
+}
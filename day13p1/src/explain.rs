@@ -0,0 +1,143 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::packet::Entry;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Verdict {
+    LeftSmaller,
+    RightSmaller,
+    LeftRanOut,
+    RightRanOut,
+}
+
+impl Verdict {
+    pub(super) fn in_order(self) -> bool {
+        matches!(self, Verdict::LeftSmaller | Verdict::LeftRanOut)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (side, what) = match self {
+            Verdict::LeftSmaller => ("Left", "is smaller"),
+            Verdict::RightSmaller => ("Right", "is smaller"),
+            Verdict::LeftRanOut => ("Left", "ran out of items"),
+            Verdict::RightRanOut => ("Right", "ran out of items"),
+        };
+        let order = if self.in_order() { "" } else { "not " };
+        write!(
+            f,
+            "{side} side {what}, so inputs are {order}in the right order"
+        )
+    }
+}
+
+// The comparison of two packets written out the way the puzzle text does it,
+// along with where it was decided.
+pub(super) struct Explanation {
+    lines: Vec<String>,
+    verdict: Option<Verdict>,
+    // Index taken in each list on the way down to the deciding element; an
+    // integer converted to a list is index 0 of that list.
+    path: Vec<usize>,
+}
+
+fn show_list(list: &[Entry]) -> String {
+    format!("[{}]", list.iter().join(","))
+}
+
+impl Explanation {
+    pub(super) fn new(left: &Entry, right: &Entry) -> Self {
+        let mut explanation = Self {
+            lines: Vec::new(),
+            verdict: None,
+            path: Vec::new(),
+        };
+        explanation.compare(left, right, 0);
+        explanation
+    }
+
+    fn line(&mut self, depth: usize, text: String) {
+        self.lines
+            .push(format!("{:indent$}- {text}", "", indent = depth * 2));
+    }
+
+    fn decide(&mut self, depth: usize, verdict: Verdict) -> bool {
+        self.line(depth, verdict.to_string());
+        self.verdict = Some(verdict);
+        true
+    }
+
+    // Each of these returns whether the comparison has been decided.
+    fn compare(&mut self, left: &Entry, right: &Entry, depth: usize) -> bool {
+        match (left, right) {
+            (Entry::Integer(int1), Entry::Integer(int2)) => {
+                self.line(depth, format!("Compare {int1} vs {int2}"));
+                match int1.cmp(int2) {
+                    Ordering::Less => self.decide(depth + 1, Verdict::LeftSmaller),
+                    Ordering::Greater => self.decide(depth + 1, Verdict::RightSmaller),
+                    Ordering::Equal => false,
+                }
+            }
+            (Entry::List(list1), Entry::List(list2)) => self.compare_lists(list1, list2, depth),
+            (Entry::Integer(_), Entry::List(list2)) => {
+                self.line(depth, format!("Compare {left} vs {right}"));
+                self.line(
+                    depth + 1,
+                    format!("Mixed types; convert left to [{left}] and retry comparison"),
+                );
+                self.compare_lists(std::slice::from_ref(left), list2, depth + 1)
+            }
+            (Entry::List(list1), Entry::Integer(_)) => {
+                self.line(depth, format!("Compare {left} vs {right}"));
+                self.line(
+                    depth + 1,
+                    format!("Mixed types; convert right to [{right}] and retry comparison"),
+                );
+                self.compare_lists(list1, std::slice::from_ref(right), depth + 1)
+            }
+        }
+    }
+
+    fn compare_lists(&mut self, list1: &[Entry], list2: &[Entry], depth: usize) -> bool {
+        self.line(
+            depth,
+            format!("Compare {} vs {}", show_list(list1), show_list(list2)),
+        );
+
+        for (index, (entry1, entry2)) in list1.iter().zip(list2).enumerate() {
+            self.path.push(index);
+            if self.compare(entry1, entry2, depth + 1) {
+                return true;
+            }
+            self.path.pop();
+        }
+
+        let verdict = match list1.len().cmp(&list2.len()) {
+            Ordering::Less => Verdict::LeftRanOut,
+            Ordering::Greater => Verdict::RightRanOut,
+            Ordering::Equal => return false,
+        };
+        self.path.push(list1.len().min(list2.len()));
+        self.decide(depth + 1, verdict)
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.lines.iter() {
+            writeln!(f, "{line}")?;
+        }
+
+        match self.verdict {
+            Some(_) => {
+                let path: String = self.path.iter().map(|i| format!("[{i}]")).collect();
+                write!(f, "Decided at packet{path}")
+            }
+            None => write!(f, "Packets are equal"),
+        }
+    }
+}
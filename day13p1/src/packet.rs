@@ -0,0 +1,203 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use itertools::Itertools;
+
+#[derive(Clone, Debug)]
+pub(super) enum Entry {
+    Integer(isize),
+    List(Vec<Entry>),
+}
+
+// Packets compare as the puzzle describes, with an integer standing in for a
+// one-element list when matched against a list. That makes [1], [[1]] and
+// [[[1]]] all equal, so equality is defined by the same comparison to keep
+// Eq and Ord in agreement.
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Entry::Integer(int1), Entry::Integer(int2)) => int1.cmp(int2),
+            (Entry::List(list1), Entry::List(list2)) => compare_lists(list1, list2),
+            (Entry::List(list1), Entry::Integer(_)) => {
+                compare_lists(list1, std::slice::from_ref(other))
+            }
+            (Entry::Integer(_), Entry::List(list2)) => {
+                compare_lists(std::slice::from_ref(self), list2)
+            }
+        }
+    }
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+// Element by element; a list that runs out first is the smaller.
+fn compare_lists(list1: &[Entry], list2: &[Entry]) -> Ordering {
+    list1
+        .iter()
+        .zip(list2)
+        .map(|(entry1, entry2)| entry1.cmp(entry2))
+        .find(|ord| ord.is_ne())
+        .unwrap_or_else(|| list1.len().cmp(&list2.len()))
+}
+
+#[derive(Debug)]
+pub(super) struct ParseError {
+    offset: usize,
+    message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at offset {}: {}", self.offset, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+// Recursive descent straight off the input bytes:
+//
+//   entry   := list | integer
+//   list    := '[' (entry (',' entry)*)? ']'
+//   integer := '-'? digit+
+//
+// with whitespace allowed between any two tokens. Lists nest no deeper than
+// MAX_DEPTH, so hostile input gets an error rather than overflowing the stack
+// here or in anything that later walks the packet.
+const MAX_DEPTH: usize = 1000;
+
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, ParseError> {
+        Err(ParseError {
+            offset: self.pos,
+            message: message.into(),
+        })
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> Result<T, ParseError> {
+        match self.source[self.pos..].chars().next() {
+            Some(c) => self.error(format!("expected {expected}, found {c:?}")),
+            None => self.error(format!("expected {expected}, found end of input")),
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        let bytes = self.source.as_bytes();
+        while bytes.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+        bytes.get(self.pos).copied()
+    }
+
+    fn entry(&mut self) -> Result<Entry, ParseError> {
+        match self.peek() {
+            Some(b'[') => self.list(),
+            Some(b'-' | b'0'..=b'9') => self.integer(),
+            _ => self.unexpected("'[' or an integer"),
+        }
+    }
+
+    fn list(&mut self) -> Result<Entry, ParseError> {
+        if self.depth == MAX_DEPTH {
+            return self.error(format!("lists nested more than {MAX_DEPTH} deep"));
+        }
+
+        self.pos += 1;
+        let mut entries = Vec::new();
+
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Entry::List(entries));
+        }
+
+        self.depth += 1;
+        loop {
+            entries.push(self.entry()?);
+
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    self.depth -= 1;
+                    return Ok(Entry::List(entries));
+                }
+                _ => return self.unexpected("',' or ']'"),
+            }
+        }
+    }
+
+    fn integer(&mut self) -> Result<Entry, ParseError> {
+        let start = self.pos;
+        let bytes = self.source.as_bytes();
+
+        if bytes[self.pos] == b'-' {
+            self.pos += 1;
+        }
+
+        let digits = self.pos;
+        while bytes.get(self.pos).is_some_and(u8::is_ascii_digit) {
+            self.pos += 1;
+        }
+
+        if self.pos == digits {
+            return self.unexpected("a digit");
+        }
+
+        match self.source[start..self.pos].parse() {
+            Ok(value) => Ok(Entry::Integer(value)),
+            Err(_) => {
+                self.pos = start;
+                self.error("integer out of range")
+            }
+        }
+    }
+}
+
+impl FromStr for Entry {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            source: s,
+            pos: 0,
+            depth: 0,
+        };
+        let entry = parser.entry()?;
+
+        if parser.peek().is_some() {
+            return parser.unexpected("end of input");
+        }
+
+        Ok(entry)
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Integer(i) => write!(f, "{i}"),
+            Entry::List(list) => {
+                write!(f, "[{}]", list.iter().join(","))
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::error::Error;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::packet::Entry;
+
+// A JSON value that isn't a packet, with the path to the offending part in
+// the form "$[3][0]".
+#[derive(Debug)]
+pub(super) struct JsonError {
+    path: String,
+    message: String,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {}: {}", self.path, self.message)
+    }
+}
+
+impl Error for JsonError {}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+fn to_entry(value: &Value, path: &mut String) -> Result<Entry, JsonError> {
+    let error = |message: String| JsonError {
+        path: path.clone(),
+        message,
+    };
+
+    match value {
+        Value::Array(values) => {
+            let mut entries = Vec::with_capacity(values.len());
+            for (index, value) in values.iter().enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{index}]"));
+                entries.push(to_entry(value, path)?);
+                path.truncate(len);
+            }
+            Ok(Entry::List(entries))
+        }
+        // Only numbers written as integers; 1.0 is a float to serde_json and
+        // so is rejected with the rest.
+        Value::Number(number) if number.is_i64() || number.is_u64() => number
+            .as_i64()
+            .and_then(|n| isize::try_from(n).ok())
+            .map(Entry::Integer)
+            .ok_or_else(|| error(format!("integer {number} out of range"))),
+        Value::Number(number) => Err(error(format!("expected an integer, found {number}"))),
+        _ => Err(error(format!(
+            "expected an integer or an array, found {}",
+            kind(value)
+        ))),
+    }
+}
+
+impl TryFrom<&Value> for Entry {
+    type Error = JsonError;
+
+    fn try_from(value: &Value) -> Result<Self, JsonError> {
+        to_entry(value, &mut "$".to_string())
+    }
+}
+
+impl From<&Entry> for Value {
+    fn from(entry: &Entry) -> Self {
+        match entry {
+            Entry::Integer(i) => Value::from(*i),
+            Entry::List(list) => Value::Array(list.iter().map(Value::from).collect()),
+        }
+    }
+}
+
+// A document holding a single array of packets, as another tool might write
+// them out.
+pub(super) fn read_packets(text: &str) -> Result<Vec<Entry>, JsonError> {
+    let value: Value = serde_json::from_str(text).map_err(|err| JsonError {
+        path: "$".to_string(),
+        message: err.to_string(),
+    })?;
+
+    let Value::Array(values) = &value else {
+        return Err(JsonError {
+            path: "$".to_string(),
+            message: format!("expected an array of packets, found {}", kind(&value)),
+        });
+    };
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| to_entry(value, &mut format!("$[{index}]")))
+        .collect()
+}
+
+pub(super) fn write_packets(packets: &[Entry]) -> String {
+    Value::Array(packets.iter().map(Value::from).collect()).to_string()
+}
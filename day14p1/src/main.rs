@@ -61,6 +61,10 @@ struct Cave {
     objects: HashMap<Point, Object>,
     min: Point,
     max: Point,
+    // The path the last grain fell along, from the source down to just above
+    // where it came to rest. The next grain follows the same path that far,
+    // so it can start from the end of it instead of from the top.
+    trail: Vec<Point>,
 }
 
 impl Cave {
@@ -89,7 +93,10 @@ impl Cave {
     }
 
     fn introduce_sand(&mut self) -> bool {
-        let mut sand = Point::from((500, 0));
+        let mut sand = match self.trail.last() {
+            Some(sand) => *sand,
+            None => return false,
+        };
 
         loop {
             let mut moved = false;
@@ -99,12 +106,14 @@ impl Cave {
 
                 if matches!(self.get(target), Object::Air) {
                     sand = target;
+                    self.trail.push(sand);
                     moved = true;
                     break;
                 }
             }
 
             if !moved {
+                self.trail.pop();
                 break;
             }
 
@@ -122,6 +131,7 @@ impl Default for Cave {
     fn default() -> Self {
         Cave {
             objects: HashMap::from([((500, 0).into(), Object::Source)]),
+            trail: vec![(500, 0).into()],
             min: (499, 0).into(),
             max: (501, 0).into(),
         }
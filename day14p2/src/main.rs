@@ -67,6 +67,10 @@ struct Cave {
     objects: HashMap<Point, Object>,
     min: Point,
     max: Point,
+    // The path the last grain fell along, from the source down to just above
+    // where it came to rest. The next grain follows the same path that far,
+    // so it can start from the end of it instead of from the top.
+    trail: Vec<Point>,
 }
 
 impl Cave {
@@ -93,15 +97,15 @@ impl Cave {
         if point.y >= self.max.y {
             Object::Rock
         } else {
-            self.objects
-                .get(&point.into())
-                .copied()
-                .unwrap_or(Object::Air)
+            self.objects.get(&point).copied().unwrap_or(Object::Air)
         }
     }
 
     fn introduce_sand(&mut self) -> bool {
-        let mut sand = Point::from((500, 0));
+        let mut sand = match self.trail.last() {
+            Some(sand) => *sand,
+            None => return false,
+        };
 
         loop {
             let mut moved = false;
@@ -111,12 +115,14 @@ impl Cave {
 
                 if matches!(self.get(target), Object::Air) {
                     sand = target;
+                    self.trail.push(sand);
                     moved = true;
                     break;
                 }
             }
 
             if !moved {
+                self.trail.pop();
                 break;
             }
         }
@@ -136,10 +142,74 @@ impl Cave {
     }
 }
 
+impl Cave {
+    // Counts the sand that comes to rest before the source is blocked, without
+    // dropping any. A cell fills exactly when it isn't rock and one of the
+    // three cells above it fills, so each row's sand is the row above widened
+    // by one either side, less that row's rock. Rows are kept as sorted,
+    // disjoint, inclusive ranges of x, which keeps deep floors cheap.
+    fn sweep(&self) -> usize {
+        let mut rocks: HashMap<isize, Vec<isize>> = HashMap::new();
+        for (point, object) in self.objects.iter() {
+            if matches!(object, Object::Rock) {
+                rocks.entry(point.y).or_default().push(point.x);
+            }
+        }
+
+        let mut row = vec![(500, 500)];
+        let mut total = 1;
+
+        for y in 1..self.max.y {
+            let mut widened: Vec<(isize, isize)> = Vec::new();
+            for (start, end) in row {
+                match widened.last_mut() {
+                    Some(last) if start - 1 <= last.1 + 1 => last.1 = end + 1,
+                    _ => widened.push((start - 1, end + 1)),
+                }
+            }
+
+            row = match rocks.get_mut(&y) {
+                Some(xs) => {
+                    xs.sort_unstable();
+                    let mut split = Vec::new();
+                    for (mut start, end) in widened {
+                        for &x in xs.iter() {
+                            if x < start {
+                                continue;
+                            } else if x > end {
+                                break;
+                            } else if x > start {
+                                split.push((start, x - 1));
+                            }
+                            start = x + 1;
+                        }
+                        if start <= end {
+                            split.push((start, end));
+                        }
+                    }
+                    split
+                }
+                None => widened,
+            };
+
+            if row.is_empty() {
+                break;
+            }
+            total += row
+                .iter()
+                .map(|(start, end)| (end - start + 1) as usize)
+                .sum::<usize>();
+        }
+
+        total
+    }
+}
+
 impl Default for Cave {
     fn default() -> Self {
         Cave {
             objects: HashMap::from([((500, 0).into(), Object::Source)]),
+            trail: vec![(500, 0).into()],
             min: (499, 0).into(),
             max: (502, 2).into(),
         }
@@ -182,6 +252,13 @@ fn main() {
         }
     }
 
+    // "sweep" counts the sand without simulating it, for floors too deep to
+    // watch fill.
+    if args.get(2).map(String::as_str) == Some("sweep") {
+        println!("{} units of sand", cave.sweep());
+        return;
+    }
+
     println!("{cave}");
     let mut units = 0;
 